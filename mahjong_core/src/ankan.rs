@@ -0,0 +1,84 @@
+use crate::decompose::{decompose_with_blocks_needed, Block};
+use crate::rules::RiichiAnkanRule;
+use crate::tile::{Tile, TileKey};
+use crate::wait::winning_tiles;
+use serde::Serialize;
+
+/// 立直後の暗槓の可否
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "result", content = "reason", rename_all = "UPPERCASE")]
+pub enum AnkanVerdict {
+    ALLOWED,
+    /// 理由つきで不可
+    FORBIDDEN(String),
+}
+
+/// 立直後の暗槓の判定
+/// - hand: 副露を除いた手牌（ツモ牌を含む 3n+2 枚）、meld_count: 副露（暗槓を含む）の数
+/// - drawn: ツモ牌、kan: 暗槓する牌
+/// - ツモ牌以外での暗槓（送り槓）と、待ちが変わる暗槓は不可
+/// - STRICT では、どの和了形でもその牌が刻子になる（面子構成が変わらない）ことも必要
+pub fn check_riichi_ankan(
+    hand: &[Tile],
+    meld_count: usize,
+    drawn: Tile,
+    kan: Tile,
+    rule: RiichiAnkanRule,
+) -> AnkanVerdict {
+    let k = TileKey::from_tile(&kan);
+    if TileKey::from_tile(&drawn) != k {
+        return AnkanVerdict::FORBIDDEN("ツモ牌以外では暗槓できません（送り槓）".into());
+    }
+    if hand.iter().filter(|t| TileKey::from_tile(t) == k).count() != 4 {
+        return AnkanVerdict::FORBIDDEN(format!("{}が4枚ありません", kan.to_code()));
+    }
+
+    let mut before = hand.to_vec();
+    let Some(pos) = before.iter().position(|t| *t == drawn) else {
+        return AnkanVerdict::FORBIDDEN(format!("ツモ牌{}が手牌にありません", drawn.to_code()));
+    };
+    before.remove(pos);
+    let after: Vec<Tile> = hand
+        .iter()
+        .filter(|t| TileKey::from_tile(t) != k)
+        .copied()
+        .collect();
+
+    let waits = winning_tiles(&before, meld_count);
+    let waits_after = winning_tiles(&after, meld_count + 1);
+    if waits != waits_after {
+        return AnkanVerdict::FORBIDDEN(format!(
+            "待ちが変わります（{} → {}）",
+            codes(&waits),
+            codes(&waits_after)
+        ));
+    }
+
+    if rule == RiichiAnkanRule::STRICT {
+        for w in &waits {
+            let mut tiles = before.clone();
+            tiles.push(w.to_tile());
+            let patterns = decompose_with_blocks_needed(&tiles, 4 - meld_count);
+            let koutsu =
+                |b: &Block| matches!(b, Block::Koutsu(t, _, _) if TileKey::from_tile(t) == k);
+            if patterns.iter().any(|p| !p.blocks.iter().any(koutsu)) {
+                return AnkanVerdict::FORBIDDEN(format!(
+                    "面子構成が変わります（{}で和了したとき{}を刻子以外に使う形があります）",
+                    w.to_tile().to_code(),
+                    kan.to_code()
+                ));
+            }
+        }
+    }
+    AnkanVerdict::ALLOWED
+}
+
+fn codes(keys: &[TileKey]) -> String {
+    if keys.is_empty() {
+        return "なし".into();
+    }
+    keys.iter()
+        .map(|k| k.to_tile().to_code())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,88 @@
+//! mjai のクライアントとして基本戦略で打つ
+//!
+//! 使い方: mjai_bot [--name 名前] [--rules ルールファイル] [--connect ホスト:ポート]
+//! - --connect がなければ標準入出力で1行1イベントをやりとりする
+//! - 1行にイベントの配列が来たときは、すべて適用して最後のイベントへの応答を返す
+//! - ルールの既定は天鳳
+
+use mahjong_core::{load_rules_file, MjaiBot, MjaiEvent, RulePreset};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut name = "mahjong_core".to_string();
+    let mut rules = RulePreset::TENHOU.rules();
+    let mut connect = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(a) = args.next() {
+        let mut value = || args.next().ok_or(format!("{} の値がありません", a));
+        match a.as_str() {
+            "--name" => name = value()?,
+            "--rules" => rules = load_rules_file(Path::new(&value()?))?,
+            "--connect" => connect = Some(value()?),
+            _ => {
+                return Err(format!(
+                    "不明な引数です: {}\n使い方: mjai_bot [--name 名前] [--rules ルールファイル] [--connect ホスト:ポート]",
+                    a
+                ))
+            }
+        }
+    }
+
+    let mut bot = MjaiBot::new(&name, rules);
+    match connect {
+        Some(addr) => {
+            let stream = TcpStream::connect(&addr)
+                .map_err(|e| format!("{} に接続できません: {}", addr, e))?;
+            let reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+            serve(&mut bot, reader, stream)
+        }
+        None => serve(&mut bot, std::io::stdin().lock(), std::io::stdout().lock()),
+    }
+}
+
+/// 1行読んで1行応答する（end_game のあとか入力が尽きたら終わる）
+/// - 知らない type のイベントには none を返す
+fn serve(bot: &mut MjaiBot, reader: impl BufRead, mut writer: impl Write) -> Result<(), String> {
+    for line in reader.lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value: serde_json::Value =
+            serde_json::from_str(&line).map_err(|e| format!("JSON が不正です: {}: {}", e, line))?;
+        let events: Vec<MjaiEvent> = match value {
+            serde_json::Value::Array(items) => items
+                .into_iter()
+                .map(serde_json::from_value)
+                .collect::<Result<_, _>>(),
+            v => serde_json::from_value(v).map(|e| vec![e]),
+        }
+        .map_err(|e| format!("mjai イベントが不正です: {}: {}", e, line))?;
+
+        let mut response = MjaiEvent::None;
+        for e in &events {
+            response = bot.react(e)?;
+        }
+        let out = serde_json::to_string(&response).unwrap();
+        writeln!(writer, "{}", out)
+            .and_then(|_| writer.flush())
+            .map_err(|e| e.to_string())?;
+        if events.iter().any(|e| matches!(e, MjaiEvent::EndGame)) {
+            break;
+        }
+    }
+    Ok(())
+}
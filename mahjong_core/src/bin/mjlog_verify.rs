@@ -0,0 +1,92 @@
+//! 天鳳の牌譜を再計算して食い違いを報告する
+//!
+//! 使い方: mjlog_verify <ファイルまたはディレクトリ>...
+//! - ディレクトリは .mjlog / .xml / .json を再帰的に読む
+//! - 食い違いは JSON で標準出力へ、集計は標準エラーへ出す
+//! - 食い違いか読み込みエラーがあれば終了コード 1
+//! - 回帰確認用の牌譜は mahjong_core/testdata/mjlog にある（mjlog_verify mahjong_core/testdata）
+
+use mahjong_core::{verify_mjlog_file, Discrepancy};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[derive(Serialize)]
+struct FileReport<'a> {
+    file: String,
+    discrepancies: &'a [Discrepancy],
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() {
+        eprintln!("使い方: mjlog_verify <ファイルまたはディレクトリ>...");
+        return ExitCode::from(2);
+    }
+
+    let mut files = vec![];
+    for a in &args {
+        collect(Path::new(a), &mut files);
+    }
+
+    let (mut rounds, mut wins, mut matched, mut failed) = (0, 0, 0, 0);
+    let mut ok = true;
+    for f in &files {
+        match verify_mjlog_file(f) {
+            Ok(report) => {
+                rounds += report.rounds;
+                wins += report.wins;
+                matched += report.matched;
+                if !report.discrepancies.is_empty() {
+                    ok = false;
+                    let out = FileReport {
+                        file: f.display().to_string(),
+                        discrepancies: &report.discrepancies,
+                    };
+                    println!("{}", serde_json::to_string(&out).unwrap());
+                }
+            }
+            Err(e) => {
+                ok = false;
+                failed += 1;
+                eprintln!("{}", e);
+            }
+        }
+    }
+
+    eprintln!(
+        "牌譜 {} 件（読み込み失敗 {} 件）、{} 局、和了 {} 件中 {} 件一致",
+        files.len(),
+        failed,
+        rounds,
+        wins,
+        matched
+    );
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn collect(path: &Path, files: &mut Vec<PathBuf>) {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(path) else {
+        files.push(path.to_path_buf());
+        return;
+    };
+    let mut entries: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+    entries.sort();
+    for p in entries {
+        let log = p
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| matches!(e, "mjlog" | "xml" | "json"));
+        if p.is_dir() || log {
+            collect(&p, files);
+        }
+    }
+}
@@ -0,0 +1,575 @@
+use crate::ankan::{check_riichi_ankan, AnkanVerdict};
+use crate::calls::{discard_calls, draw_calls, kuikae, meld, meld_key, meld_tiles};
+use crate::dora::indicator_to_dora;
+use crate::furiten::FuritenInfo;
+use crate::mjai::{mjai_tile, to_mjai_tile, MjaiEvent};
+use crate::mjlog::wind;
+use crate::rules::RuleSet;
+use crate::score::score;
+use crate::shanten::shanten;
+use crate::tile::{Honor, Suit, Tile, TileKey};
+use crate::wait::is_complete;
+use crate::{Flags, Meld, MeldType, Riichi, ScoreRequest, WinType, Wind};
+
+/// mjai のクライアントとして打つ基本戦略のボット
+/// - 打牌: 向聴数が最小で、受け入れ枚数が最大の牌（同じなら字牌・端の牌から。赤五・ドラは残す）
+/// - 鳴き: 役牌のポン、喰いタンか役牌を鳴いているときの向聴数が進むチー・ポン（大明槓はしない）
+/// - 暗槓・加槓: 向聴数が下がらないとき（立直後は rules.riichi_ankan で許される暗槓のみ）
+/// - 門前で聴牌したら立直、役があって和了できれば和了
+pub struct MjaiBot {
+    name: String,
+    rules: RuleSet,
+    seat: Option<usize>,
+    round_wind: Wind,
+    dealer: usize,
+    honba: u32,
+    kyotaku: u32,
+    dora: Vec<Tile>,
+    hand: Vec<Tile>,
+    melds: Vec<Meld>,
+    /// 見えている牌の枚数（自分の手牌・全員の河と副露・ドラ表示牌）
+    seen: [u8; 34],
+    /// 残りのツモ牌の枚数
+    wall: usize,
+    drawn: Option<Tile>,
+    riichi: Riichi,
+    /// 立直宣言の打牌（reach が返ってきたら切る）
+    reach_discard: Option<Tile>,
+    /// 鳴いた直後に切れない牌
+    kuikae: Vec<TileKey>,
+    furiten: FuritenInfo,
+    /// 嶺上牌のツモ
+    rinshan: bool,
+    /// まだ第一打をしていない
+    first_turn: bool,
+    /// 鳴き（暗槓を含む）で第一巡が途切れた
+    interrupted: bool,
+}
+
+impl MjaiBot {
+    pub fn new(name: &str, rules: RuleSet) -> MjaiBot {
+        MjaiBot {
+            name: name.to_string(),
+            rules,
+            seat: None,
+            round_wind: Wind::E,
+            dealer: 0,
+            honba: 0,
+            kyotaku: 0,
+            dora: vec![],
+            hand: vec![],
+            melds: vec![],
+            seen: [0; 34],
+            wall: 0,
+            drawn: None,
+            riichi: Riichi::NONE,
+            reach_discard: None,
+            kuikae: vec![],
+            furiten: FuritenInfo::default(),
+            rinshan: false,
+            first_turn: true,
+            interrupted: false,
+        }
+    }
+
+    /// サーバからのイベント1つに応答する（何もしないときは none）
+    pub fn react(&mut self, event: &MjaiEvent) -> Result<MjaiEvent, String> {
+        let none = Ok(MjaiEvent::None);
+        match event {
+            MjaiEvent::Hello { .. } => {
+                return Ok(MjaiEvent::Join {
+                    name: self.name.clone(),
+                    room: "default".into(),
+                })
+            }
+            MjaiEvent::StartGame { id, .. } => {
+                self.seat = *id;
+                return none;
+            }
+            MjaiEvent::Error { message } => return Err(format!("サーバのエラー: {}", message)),
+            _ => {}
+        }
+        let me = self.seat.ok_or("start_game で席が通知されていません")?;
+
+        match event {
+            MjaiEvent::StartKyoku {
+                bakaze,
+                honba,
+                kyotaku,
+                oya,
+                dora_marker,
+                tehais,
+                ..
+            } => {
+                let hand = tehais
+                    .get(me)
+                    .ok_or("配牌に自分の席がありません")?
+                    .iter()
+                    .map(|p| mjai_tile(p))
+                    .collect::<Result<Vec<_>, _>>()?;
+                *self = MjaiBot {
+                    round_wind: match bakaze.as_str() {
+                        "S" => Wind::S,
+                        "W" => Wind::W,
+                        "N" => Wind::N,
+                        _ => Wind::E,
+                    },
+                    dealer: *oya,
+                    honba: *honba,
+                    kyotaku: *kyotaku,
+                    wall: if self.rules.sanma { 108 } else { 136 } - 14 - 13 * self.rules.players(),
+                    seat: Some(me),
+                    ..MjaiBot::new(&self.name, self.rules.clone())
+                };
+                for t in &hand {
+                    self.see(t);
+                }
+                self.hand = hand;
+                let d = mjai_tile(dora_marker)?;
+                self.see(&d);
+                self.dora.push(d);
+                none
+            }
+            MjaiEvent::Tsumo { actor, pai } => {
+                self.wall = self.wall.saturating_sub(1);
+                if *actor != me {
+                    return none;
+                }
+                let t = mjai_tile(pai)?;
+                self.see(&t);
+                self.hand.push(t);
+                self.drawn = Some(t);
+                Ok(self.on_draw(me))
+            }
+            MjaiEvent::Dahai { actor, pai, .. } => {
+                let t = mjai_tile(pai)?;
+                if *actor == me {
+                    remove(&mut self.hand, &t)?;
+                    self.furiten.discards.push(t);
+                    self.furiten.passed.clear();
+                    self.first_turn = false;
+                    self.kuikae.clear();
+                    self.drawn = None;
+                    self.rinshan = false;
+                    return none;
+                }
+                self.see(&t);
+                let response = self.on_discard(me, *actor, t, false);
+                self.pass(t);
+                Ok(response)
+            }
+            MjaiEvent::Chi {
+                actor,
+                pai,
+                consumed,
+                ..
+            }
+            | MjaiEvent::Pon {
+                actor,
+                pai,
+                consumed,
+                ..
+            }
+            | MjaiEvent::Daiminkan {
+                actor,
+                pai,
+                consumed,
+                ..
+            } => {
+                self.interrupted = true;
+                let called = mjai_tile(pai)?;
+                let consumed = consumed
+                    .iter()
+                    .map(|p| mjai_tile(p))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if *actor != me {
+                    consumed.iter().for_each(|t| self.see(t));
+                    return none;
+                }
+                for t in &consumed {
+                    remove(&mut self.hand, t)?;
+                }
+                let mut tiles = consumed;
+                tiles.push(called);
+                let meld_type = match event {
+                    MjaiEvent::Chi { .. } => {
+                        tiles.sort_by_key(|t| t.num);
+                        MeldType::CHI
+                    }
+                    MjaiEvent::Pon { .. } => MeldType::PON,
+                    _ => MeldType::MINKAN,
+                };
+                let m = meld(meld_type, &tiles);
+                self.melds.push(m.clone());
+                if meld_type == MeldType::MINKAN {
+                    self.rinshan = true;
+                    return none;
+                }
+                self.kuikae = kuikae(&m, called, self.rules.kuikae);
+                let (t, _) = self.choose_discard();
+                Ok(self.dahai(me, t))
+            }
+            MjaiEvent::Ankan { actor, consumed } => {
+                self.interrupted = true;
+                let tiles = consumed
+                    .iter()
+                    .map(|p| mjai_tile(p))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if *actor != me {
+                    tiles.iter().for_each(|t| self.see(t));
+                    return none;
+                }
+                for t in &tiles {
+                    remove(&mut self.hand, t)?;
+                }
+                self.melds.push(meld(MeldType::ANKAN, &tiles));
+                self.rinshan = true;
+                none
+            }
+            MjaiEvent::Kakan { actor, pai, .. } => {
+                let t = mjai_tile(pai)?;
+                if *actor != me {
+                    self.see(&t);
+                    let response = self.on_discard(me, *actor, t, true);
+                    self.pass(t);
+                    return Ok(response);
+                }
+                remove(&mut self.hand, &t)?;
+                let k = TileKey::from_tile(&t);
+                if let Some(m) = self
+                    .melds
+                    .iter_mut()
+                    .find(|m| m.meld_type == MeldType::PON && meld_key(m) == k)
+                {
+                    let mut tiles = meld_tiles(m);
+                    tiles.push(t);
+                    *m = meld(MeldType::MINKAN, &tiles);
+                }
+                self.rinshan = true;
+                none
+            }
+            MjaiEvent::Dora { dora_marker } => {
+                let d = mjai_tile(dora_marker)?;
+                self.see(&d);
+                self.dora.push(d);
+                none
+            }
+            MjaiEvent::Reach { actor } if *actor == me => {
+                self.riichi = if self.first_turn && !self.interrupted {
+                    Riichi::DOUBLE
+                } else {
+                    Riichi::RIICHI
+                };
+                let t = self
+                    .reach_discard
+                    .take()
+                    .or(self.drawn)
+                    .ok_or("立直宣言の打牌がありません")?;
+                Ok(self.dahai(me, t))
+            }
+            MjaiEvent::ReachAccepted { .. } => {
+                self.kyotaku += 1;
+                none
+            }
+            _ => none,
+        }
+    }
+
+    /// 自分のツモ：ツモ和了・槓・立直・打牌
+    fn on_draw(&mut self, me: usize) -> MjaiEvent {
+        let drawn = self.drawn.unwrap();
+        if self.win_points(me, drawn, None, false) > 0 {
+            return MjaiEvent::Hora {
+                actor: me,
+                target: me,
+                pai: Some(to_mjai_tile(&drawn)),
+                uradora_markers: vec![],
+                hora_tehais: vec![],
+                yakus: vec![],
+                fu: None,
+                fan: None,
+                hora_points: None,
+                deltas: vec![],
+                scores: None,
+            };
+        }
+
+        if self.wall > 0 {
+            let current = shanten(&self.hand, self.melds.len());
+            for opt in draw_calls(&self.hand, &self.melds) {
+                let tiles = meld_tiles(&opt.meld);
+                let ankan = opt.meld.meld_type == MeldType::ANKAN;
+                let ok = if self.riichi != Riichi::NONE {
+                    ankan
+                        && check_riichi_ankan(
+                            &self.hand,
+                            self.melds.len(),
+                            drawn,
+                            tiles[0],
+                            self.rules.riichi_ankan,
+                        ) == AnkanVerdict::ALLOWED
+                } else {
+                    opt.shanten <= current
+                };
+                if !ok {
+                    continue;
+                }
+                let consumed: Vec<String> = tiles.iter().map(to_mjai_tile).collect();
+                return if ankan {
+                    MjaiEvent::Ankan {
+                        actor: me,
+                        consumed,
+                    }
+                } else {
+                    MjaiEvent::Kakan {
+                        actor: me,
+                        pai: consumed[3].clone(),
+                        consumed: consumed[..3].to_vec(),
+                    }
+                };
+            }
+        }
+
+        if self.riichi != Riichi::NONE {
+            return self.dahai(me, drawn);
+        }
+        let (t, after) = self.choose_discard();
+        let menzen = self.melds.iter().all(|m| m.meld_type == MeldType::ANKAN);
+        if menzen && after == 0 && self.wall >= self.rules.players() {
+            self.reach_discard = Some(t);
+            return MjaiEvent::Reach { actor: me };
+        }
+        self.dahai(me, t)
+    }
+
+    /// 他家の打牌・加槓：ロン・チー・ポン
+    fn on_discard(&self, me: usize, from: usize, tile: Tile, chankan: bool) -> MjaiEvent {
+        if self.win_points(me, tile, Some(from), chankan) > 0 {
+            return MjaiEvent::Hora {
+                actor: me,
+                target: from,
+                pai: Some(to_mjai_tile(&tile)),
+                uradora_markers: vec![],
+                hora_tehais: vec![],
+                yakus: vec![],
+                fu: None,
+                fan: None,
+                hora_points: None,
+                deltas: vec![],
+                scores: None,
+            };
+        }
+        if chankan || self.riichi != Riichi::NONE || self.wall == 0 {
+            return MjaiEvent::None;
+        }
+
+        let current = shanten(&self.hand, self.melds.len());
+        let has_yakuhai = self
+            .melds
+            .iter()
+            .any(|m| m.meld_type != MeldType::CHI && self.yakuhai(meld_key(m)));
+        let best = discard_calls(&self.hand, &self.melds, tile, me, from, &self.rules)
+            .into_iter()
+            .filter(|opt| opt.meld.meld_type != MeldType::MINKAN)
+            .filter(|opt| {
+                let yakuhai_pon =
+                    opt.meld.meld_type == MeldType::PON && self.yakuhai(meld_key(&opt.meld));
+                (yakuhai_pon && opt.shanten <= current)
+                    || (opt.shanten < current && (has_yakuhai || self.tanyao_ok(&opt.meld)))
+            })
+            .min_by_key(|opt| (opt.shanten, opt.meld.meld_type == MeldType::CHI));
+        let Some(opt) = best else {
+            return MjaiEvent::None;
+        };
+
+        let mut consumed = meld_tiles(&opt.meld);
+        let pos = consumed.iter().position(|t| *t == tile).unwrap();
+        consumed.remove(pos);
+        let (pai, consumed) = (
+            to_mjai_tile(&tile),
+            consumed.iter().map(to_mjai_tile).collect(),
+        );
+        if opt.meld.meld_type == MeldType::CHI {
+            MjaiEvent::Chi {
+                actor: me,
+                target: from,
+                pai,
+                consumed,
+            }
+        } else {
+            MjaiEvent::Pon {
+                actor: me,
+                target: from,
+                pai,
+                consumed,
+            }
+        }
+    }
+
+    /// 向聴数が最小・受け入れ枚数が最大の打牌と、打牌後の向聴数
+    fn choose_discard(&self) -> (Tile, i32) {
+        let meld_count = self.melds.len();
+        let mut best: Option<(Tile, (i32, i32, i32))> = None;
+        for (i, t) in self.hand.iter().enumerate() {
+            if self.kuikae.contains(&TileKey::from_tile(t)) || self.hand[..i].contains(t) {
+                continue;
+            }
+            let mut rest = self.hand.clone();
+            rest.remove(i);
+            let sh = shanten(&rest, meld_count);
+            let key = (sh, -self.ukeire(&rest, sh), self.keep_value(t));
+            if best.as_ref().is_none_or(|(_, b)| key < *b) {
+                best = Some((*t, key));
+            }
+        }
+        // 喰い替えで切れる牌がないことはない（鳴きの候補から除いている）
+        let (t, (sh, _, _)) = best.unwrap_or((self.hand[self.hand.len() - 1], (8, 0, 0)));
+        (t, sh)
+    }
+
+    /// 向聴数が下がるツモの残り枚数
+    fn ukeire(&self, hand: &[Tile], sh: i32) -> i32 {
+        let mut total = 0;
+        let mut tiles = hand.to_vec();
+        for k in TileKey::all() {
+            let left = 4 - self.seen[k.index()] as i32;
+            if left <= 0 {
+                continue;
+            }
+            tiles.push(k.to_tile());
+            if shanten(&tiles, self.melds.len()) < sh {
+                total += left;
+            }
+            tiles.pop();
+        }
+        total
+    }
+
+    /// 残したい度合い（小さいほど先に切る）
+    fn keep_value(&self, t: &Tile) -> i32 {
+        let k = TileKey::from_tile(t);
+        let mut v = match (k.suit, k.num) {
+            (Suit::Honor, _) if self.yakuhai(k) => 1,
+            (Suit::Honor, _) => 0,
+            (_, 1 | 9) => 1,
+            (_, 2 | 8) => 2,
+            _ => 3,
+        };
+        if t.red {
+            v += 10;
+        }
+        v += 5 * self
+            .dora
+            .iter()
+            .filter(|d| TileKey::from_tile(&indicator_to_dora(**d, self.rules.sanma)) == k)
+            .count() as i32;
+        v
+    }
+
+    fn yakuhai(&self, k: TileKey) -> bool {
+        let players = self.rules.players();
+        let seat_wind = wind((self.seat.unwrap_or(0) + players - self.dealer) % players);
+        match k.honor {
+            Some(Honor::White | Honor::Green | Honor::Red) => true,
+            Some(h) => h == wind_honor(self.round_wind) || h == wind_honor(seat_wind),
+            None => false,
+        }
+    }
+
+    /// 喰いタンを狙える鳴き（鳴く牌がすべて中張牌で、手牌の么九牌が2枚以下）
+    fn tanyao_ok(&self, m: &Meld) -> bool {
+        let simple = |t: &Tile| t.suit != Suit::Honor && (2..=8).contains(&t.num);
+        self.rules.kuitan
+            && meld_tiles(m).iter().all(simple)
+            && self.melds.iter().all(|m| meld_tiles(m).iter().all(simple))
+            && self.hand.iter().filter(|t| !simple(t)).count() <= 2
+    }
+
+    /// 和了したときの点数（形がない・役がない・フリテンなら 0）
+    fn win_points(&self, me: usize, tile: Tile, loser: Option<usize>, chankan: bool) -> u32 {
+        let mut tiles = self.hand.clone();
+        if loser.is_some() {
+            tiles.push(tile);
+        }
+        if !is_complete(&tiles, self.melds.len()) {
+            return 0;
+        }
+        let mut hand = self.hand.clone();
+        let tsumo = loser.is_none();
+        if tsumo && remove(&mut hand, &tile).is_err() {
+            return 0;
+        }
+        let first = self.first_turn && !self.interrupted && tsumo;
+        let codes = |ts: &[Tile]| ts.iter().map(|t| t.to_code()).collect::<Vec<_>>();
+        let players = self.rules.players();
+        let req = ScoreRequest {
+            round_wind: self.round_wind,
+            seat_wind: wind((me + players - self.dealer) % players),
+            kyotaku: self.kyotaku,
+            honba: self.honba,
+            win_type: if tsumo { WinType::TSUMO } else { WinType::RON },
+            dealer: me == self.dealer,
+            hand_tiles: codes(&hand),
+            win_tile: tile.to_code(),
+            melds: self.melds.clone(),
+            dora_indicators: codes(&self.dora[..1]),
+            kan_dora_indicators: codes(&self.dora[1..]),
+            ura_indicators: vec![],
+            kan_ura_indicators: vec![],
+            nukidora: 0,
+            flags: Flags {
+                riichi: self.riichi,
+                rinshan: tsumo && self.rinshan,
+                chankan,
+                haitei: tsumo && self.wall == 0 && !self.rinshan,
+                houtei: !tsumo && self.wall == 0 && !chankan,
+                tenhou: first && me == self.dealer,
+                chihou: first && me != self.dealer,
+                ..Default::default()
+            },
+            events: None,
+            furiten: (!tsumo).then(|| self.furiten.clone()),
+            rules: self.rules.clone(),
+        };
+        score(&req).total_points
+    }
+
+    fn dahai(&self, me: usize, t: Tile) -> MjaiEvent {
+        MjaiEvent::Dahai {
+            actor: me,
+            pai: to_mjai_tile(&t),
+            tsumogiri: self.drawn == Some(t),
+        }
+    }
+
+    fn see(&mut self, t: &Tile) {
+        let i = TileKey::from_tile(t).index();
+        self.seen[i] = (self.seen[i] + 1).min(4);
+    }
+
+    /// 他家の打牌・加槓を見逃した
+    fn pass(&mut self, t: Tile) {
+        self.furiten.passed.push(t);
+        if self.riichi != Riichi::NONE {
+            self.furiten.riichi_passed.push(t);
+        }
+    }
+}
+
+fn remove(hand: &mut Vec<Tile>, t: &Tile) -> Result<(), String> {
+    let i = hand
+        .iter()
+        .position(|h| h == t)
+        .ok_or_else(|| format!("手牌に{}がありません", t.to_code()))?;
+    hand.remove(i);
+    Ok(())
+}
+
+fn wind_honor(w: Wind) -> Honor {
+    match w {
+        Wind::E => Honor::East,
+        Wind::S => Honor::South,
+        Wind::W => Honor::West,
+        Wind::N => Honor::North,
+    }
+}
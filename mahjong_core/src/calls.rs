@@ -0,0 +1,243 @@
+use crate::rules::{KuikaeRule, RuleSet};
+use crate::shanten::shanten;
+use crate::tile::{Suit, Tile, TileKey};
+use crate::{Meld, MeldType};
+use serde::Serialize;
+
+/// 鳴き（チー・ポン・槓）の候補
+#[derive(Debug, Clone, Serialize)]
+pub struct CallOption {
+    pub meld: Meld,
+    /// 鳴いた直後に切れない牌（喰い替え。赤五は区別しない）
+    pub kuikae: Vec<Tile>,
+    /// 鳴いた後の向聴数
+    /// - チー・ポン: 切れる牌のうち最善の打牌をした後
+    /// - 槓: 嶺上牌をツモる前
+    pub shanten: i32,
+}
+
+/// 他家の打牌へのチー・ポン・明槓（赤五の使い分けも別の候補）
+/// - hand: 副露を除いた手牌、melds: 自分の副露
+/// - チーは上家（seat の1つ前の from）の打牌のみ。三麻ではチーなし
+/// - 喰い替えで切れる牌がなくなる鳴きは含めない
+pub fn discard_calls(
+    hand: &[Tile],
+    melds: &[Meld],
+    tile: Tile,
+    seat: usize,
+    from: usize,
+    rules: &RuleSet,
+) -> Vec<CallOption> {
+    let meld_count = melds.len() + 1;
+    call_melds(hand, tile, seat, from, rules)
+        .into_iter()
+        .map(|(meld, kuikae)| {
+            let mut rest = hand.to_vec();
+            remove_used(&mut rest, &meld, Some(tile));
+            let shanten = if meld.meld_type == MeldType::MINKAN {
+                shanten(&rest, meld_count)
+            } else {
+                let mut best = i32::MAX;
+                for (i, t) in rest.iter().enumerate() {
+                    if kuikae.contains(&TileKey::from_tile(t)) {
+                        continue;
+                    }
+                    let mut after = rest.clone();
+                    after.remove(i);
+                    best = best.min(shanten(&after, meld_count));
+                }
+                best
+            };
+            CallOption {
+                meld,
+                kuikae: kuikae.into_iter().map(|k| k.to_tile()).collect(),
+                shanten,
+            }
+        })
+        .collect()
+}
+
+/// 自分のツモ番の暗槓・加槓（hand はツモ牌を含む）
+pub fn draw_calls(hand: &[Tile], melds: &[Meld]) -> Vec<CallOption> {
+    kan_melds(hand, melds)
+        .into_iter()
+        .map(|meld| {
+            let mut rest = hand.to_vec();
+            remove_used(&mut rest, &meld, None);
+            let meld_count = if meld.meld_type == MeldType::ANKAN {
+                melds.len() + 1
+            } else {
+                melds.len()
+            };
+            CallOption {
+                shanten: shanten(&rest, meld_count),
+                meld,
+                kuikae: vec![],
+            }
+        })
+        .collect()
+}
+
+/// チー・ポン・明槓の候補と、それぞれの喰い替えで切れない牌
+pub(crate) fn call_melds(
+    hand: &[Tile],
+    tile: Tile,
+    seat: usize,
+    from: usize,
+    rules: &RuleSet,
+) -> Vec<(Meld, Vec<TileKey>)> {
+    let k = TileKey::from_tile(&tile);
+    let mut options: Vec<Meld> = vec![];
+    let mut push = |m: Meld| {
+        if !options.contains(&m) {
+            options.push(m);
+        }
+    };
+
+    let same: Vec<Tile> = hand
+        .iter()
+        .filter(|t| TileKey::from_tile(t) == k)
+        .copied()
+        .collect();
+    for i in 0..same.len() {
+        for j in i + 1..same.len() {
+            push(meld(MeldType::PON, &[same[i], same[j], tile]));
+        }
+    }
+    if same.len() == 3 {
+        push(meld(MeldType::MINKAN, &[same[0], same[1], same[2], tile]));
+    }
+
+    if !rules.sanma && seat == (from + 1) % rules.players() && k.suit != Suit::Honor {
+        let num = k.num as i8;
+        for (a, b) in [(-2, -1), (-1, 1), (1, 2)] {
+            let (na, nb) = (num + a, num + b);
+            if !(1..=9).contains(&na) || !(1..=9).contains(&nb) {
+                continue;
+            }
+            let pick = |n: i8| -> Vec<Tile> {
+                hand.iter()
+                    .filter(|t| t.suit == k.suit && t.num == n as u8)
+                    .copied()
+                    .collect()
+            };
+            for ta in pick(na) {
+                for tb in pick(nb) {
+                    let mut tiles = vec![ta, tb, tile];
+                    tiles.sort_by_key(|t| t.num);
+                    push(meld(MeldType::CHI, &tiles));
+                }
+            }
+        }
+    }
+
+    options
+        .into_iter()
+        .filter_map(|m| {
+            let forbidden = kuikae(&m, tile, rules.kuikae);
+            let mut rest = hand.to_vec();
+            remove_used(&mut rest, &m, Some(tile));
+            let can_discard = m.meld_type == MeldType::MINKAN
+                || rest
+                    .iter()
+                    .any(|t| !forbidden.contains(&TileKey::from_tile(t)));
+            can_discard.then_some((m, forbidden))
+        })
+        .collect()
+}
+
+/// 暗槓・加槓の候補
+pub(crate) fn kan_melds(hand: &[Tile], melds: &[Meld]) -> Vec<Meld> {
+    let mut options = vec![];
+
+    let mut keys: Vec<TileKey> = vec![];
+    for t in hand {
+        let k = TileKey::from_tile(t);
+        if !keys.contains(&k) {
+            keys.push(k);
+        }
+    }
+    for k in keys {
+        let same: Vec<Tile> = hand
+            .iter()
+            .filter(|t| TileKey::from_tile(t) == k)
+            .copied()
+            .collect();
+        if same.len() == 4 {
+            options.push(meld(MeldType::ANKAN, &same));
+        }
+    }
+
+    for m in melds {
+        if m.meld_type != MeldType::PON {
+            continue;
+        }
+        let k = meld_key(m);
+        if let Some(t) = hand.iter().find(|t| TileKey::from_tile(t) == k) {
+            let mut tiles = meld_tiles(m);
+            tiles.push(*t);
+            options.push(meld(MeldType::MINKAN, &tiles));
+        }
+    }
+    options
+}
+
+/// チー・ポンの直後に切れない牌
+/// - GENBUTSU: 鳴いた牌と同じ牌
+/// - SUJI: さらに、端の牌でチーした順子の反対側の筋（34m に 2m をチーしたら 5m）
+pub(crate) fn kuikae(m: &Meld, called: Tile, rule: KuikaeRule) -> Vec<TileKey> {
+    let k = TileKey::from_tile(&called);
+    if rule == KuikaeRule::ALLOW || m.meld_type == MeldType::MINKAN {
+        return vec![];
+    }
+    let mut forbidden = vec![k];
+    if rule == KuikaeRule::SUJI && m.meld_type == MeldType::CHI {
+        let low = meld_tiles(m).iter().map(|t| t.num).min().unwrap();
+        let suji = if k.num == low {
+            k.num + 3
+        } else if k.num == low + 2 {
+            k.num - 3
+        } else {
+            0
+        };
+        if (1..=9).contains(&suji) {
+            forbidden.push(TileKey { num: suji, ..k });
+        }
+    }
+    forbidden
+}
+
+/// 鳴きに使った手牌を除く（called は他家から鳴いた牌）
+fn remove_used(hand: &mut Vec<Tile>, m: &Meld, called: Option<Tile>) {
+    let mut tiles = meld_tiles(m);
+    if let Some(c) = called {
+        let pos = tiles.iter().position(|t| *t == c).unwrap();
+        tiles.remove(pos);
+    } else if m.meld_type == MeldType::MINKAN {
+        // 加槓は手から1枚だけ
+        tiles = vec![*tiles.last().unwrap()];
+    }
+    for t in tiles {
+        if let Some(i) = hand.iter().position(|h| *h == t) {
+            hand.remove(i);
+        }
+    }
+}
+
+pub(crate) fn meld(meld_type: MeldType, tiles: &[Tile]) -> Meld {
+    Meld {
+        meld_type,
+        tiles: tiles.iter().map(|t| t.to_code()).collect(),
+    }
+}
+
+pub(crate) fn meld_tiles(m: &Meld) -> Vec<Tile> {
+    m.tiles
+        .iter()
+        .map(|s| Tile::from_code(s).unwrap())
+        .collect()
+}
+
+pub(crate) fn meld_key(m: &Meld) -> TileKey {
+    TileKey::from_tile(&Tile::from_code(&m.tiles[0]).unwrap())
+}
@@ -0,0 +1,149 @@
+use crate::points::{calc_points, draw_deltas, han_fu_candidates, payment_deltas};
+use crate::rules::RuleSet;
+use crate::settlement::rank_order;
+use crate::WinType;
+use serde::{Deserialize, Serialize};
+
+/// オーラス条件計算の入力
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ConditionRequest {
+    /// 起家から順の持ち点（供託に出した立直棒は差し引き済み）
+    pub points: Vec<i32>,
+    /// 条件を調べる席
+    pub seat: usize,
+    /// 親の席
+    pub dealer: usize,
+    pub honba: u32,
+    pub kyotaku: u32,
+
+    #[serde(default, deserialize_with = "crate::preset::deserialize_rules")]
+    pub rules: RuleSet,
+}
+
+/// ある順位に届く最小の和了
+#[derive(Debug, Clone, Serialize)]
+pub struct RankRequirement {
+    pub rank: u32,
+    pub han: u32,
+    /// 5翻以上・役満は 0
+    pub fu: u32,
+    pub yakuman: u32,
+    pub total_points: u32,
+}
+
+/// 和了方法ごとの条件（loser: None ならツモ）
+#[derive(Debug, Clone, Serialize)]
+pub struct WinCondition {
+    pub loser: Option<usize>,
+    pub requirements: Vec<RankRequirement>,
+}
+
+/// 流局時の聴牌/不聴の組み合わせと、そのときの順位
+#[derive(Debug, Clone, Serialize)]
+pub struct DrawCondition {
+    pub tenpai: Vec<bool>,
+    pub rank: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConditionReport {
+    pub current_rank: u32,
+    pub wins: Vec<WinCondition>,
+    pub draws: Vec<DrawCondition>,
+}
+
+/// オーラスの条件計算
+/// - 和了：ロン（放銃者ごと）/ツモ それぞれで、現在より上の各順位に届く最小の翻・符
+/// - 流局：全員の聴牌/不聴の組み合わせごとの順位
+pub fn solve_conditions(req: &ConditionRequest) -> Result<ConditionReport, String> {
+    let players = req.points.len();
+    if req.seat >= players || req.dealer >= players {
+        return Err(format!(
+            "席番号が不正です: seat={}, dealer={}",
+            req.seat, req.dealer
+        ));
+    }
+
+    let current_rank = rank_of(&req.points, req.seat);
+
+    let mut losers: Vec<Option<usize>> =
+        (0..players).filter(|&s| s != req.seat).map(Some).collect();
+    losers.push(None);
+
+    let mut wins = vec![];
+    for loser in losers {
+        let win_type = if loser.is_some() {
+            WinType::RON
+        } else {
+            WinType::TSUMO
+        };
+
+        // (han, fu, yakuman, 点数移動) を総得点の昇順に並べる
+        let mut options: Vec<(u32, u32, u32, u32, Vec<i32>)> = han_fu_candidates(win_type)
+            .into_iter()
+            .map(|(han, fu)| (han, fu, 0))
+            .chain(std::iter::once((0, 0, 1)))
+            .map(|(han, fu, yakuman)| {
+                let bd = calc_points(
+                    fu,
+                    han,
+                    yakuman,
+                    win_type,
+                    req.seat == req.dealer,
+                    req.honba,
+                    req.kyotaku,
+                    &req.rules,
+                );
+                let deltas = payment_deltas(&bd, players, req.dealer, req.seat, loser);
+                (han, fu, yakuman, bd.total_points, deltas)
+            })
+            .collect();
+        options.sort_by_key(|o| (o.3, o.2, o.0));
+
+        let mut requirements: Vec<RankRequirement> = vec![];
+        for (han, fu, yakuman, total_points, deltas) in options {
+            let after = apply(&req.points, &deltas);
+            let rank = rank_of(&after, req.seat);
+            // 既に記録した順位より良くなった時だけ追加（＝各順位の最小条件）
+            let best_so_far = requirements.last().map(|r| r.rank).unwrap_or(current_rank);
+            if rank < best_so_far {
+                requirements.push(RankRequirement {
+                    rank,
+                    han,
+                    fu,
+                    yakuman,
+                    total_points,
+                });
+            }
+        }
+
+        wins.push(WinCondition {
+            loser,
+            requirements,
+        });
+    }
+
+    let mut draws = vec![];
+    for mask in 0..(1u32 << players) {
+        let tenpai: Vec<bool> = (0..players).map(|s| mask & (1 << s) != 0).collect();
+        let after = apply(&req.points, &draw_deltas(&tenpai));
+        draws.push(DrawCondition {
+            rank: rank_of(&after, req.seat),
+            tenpai,
+        });
+    }
+
+    Ok(ConditionReport {
+        current_rank,
+        wins,
+        draws,
+    })
+}
+
+fn apply(points: &[i32], deltas: &[i32]) -> Vec<i32> {
+    points.iter().zip(deltas).map(|(p, d)| p + d).collect()
+}
+
+fn rank_of(points: &[i32], seat: usize) -> u32 {
+    rank_order(points).iter().position(|&s| s == seat).unwrap() as u32 + 1
+}
@@ -77,11 +77,6 @@ impl HandPattern {
     }
 }
 
-/// 従来互換：副露なし想定（4面子1雀頭）
-pub fn decompose_standard_hand(tiles: &[Tile]) -> Vec<HandPattern> {
-    decompose_with_blocks_needed(tiles, 4)
-}
-
 /// ✅ 副露あり対応：必要な面子数を指定して分解する
 /// blocks_needed: 手牌側で分解したい面子数（= 4 - 副露ブロック数）
 pub fn decompose_with_blocks_needed(tiles: &[Tile], blocks_needed: usize) -> Vec<HandPattern> {
@@ -194,7 +189,7 @@ fn min_nonzero_key(counts: &HashMap<TileKey, u8>) -> Option<TileKey> {
         .iter()
         .filter(|(_, &v)| v > 0)
         .map(|(k, _)| *k)
-        .min_by_key(|k| key_rank(k))
+        .min_by_key(key_rank)
 }
 
 fn key_rank(k: &TileKey) -> (u8, u8, u8) {
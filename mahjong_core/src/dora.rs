@@ -1,7 +1,8 @@
-use crate::tile::{Honor, Tile};
+use crate::tile::{Honor, Suit, Tile};
 
 /// Convert dora indicator tile -> actual dora tile (red flag is always false here)
-pub fn indicator_to_dora(ind: Tile) -> Tile {
+/// - sanma: 2m-8m are removed, so a 1m indicator wraps to 9m
+pub fn indicator_to_dora(ind: Tile, sanma: bool) -> Tile {
     if ind.is_honor() {
         let h = ind.honor.expect("honor must have honor enum");
         let next = match h {
@@ -17,7 +18,13 @@ pub fn indicator_to_dora(ind: Tile) -> Tile {
     } else {
         let suit = ind.suit;
         let n = ind.num;
-        let next = if n == 9 { 1 } else { n + 1 };
+        let next = if n == 9 {
+            1
+        } else if sanma && suit == Suit::Man && n == 1 {
+            9
+        } else {
+            n + 1
+        };
         Tile {
             suit,
             num: next,
@@ -29,8 +36,11 @@ pub fn indicator_to_dora(ind: Tile) -> Tile {
 
 /// Count dora in given tiles (hand + win tile + meld tiles) based on indicator list.
 /// - red fives counted separately by caller
-pub fn count_dora_from_indicators(all_tiles: &[Tile], indicators: &[Tile]) -> u32 {
-    let doras: Vec<_> = indicators.iter().map(|&t| indicator_to_dora(t)).collect();
+pub fn count_dora_from_indicators(all_tiles: &[Tile], indicators: &[Tile], sanma: bool) -> u32 {
+    let doras: Vec<_> = indicators
+        .iter()
+        .map(|&t| indicator_to_dora(t, sanma))
+        .collect();
 
     let mut count = 0u32;
     for tile in all_tiles {
@@ -1,13 +1,15 @@
 use crate::decompose::{decompose_with_blocks_needed, Block, OpenInfo};
 use crate::dora::{count_aka, count_dora_from_indicators};
 use crate::fu::calc_fu;
+use crate::local_yaku::{eval_local_yaku, merge_local_yaku};
 use crate::points::calc_points;
-use crate::special::{detect_special, SpecialHand};
+use crate::special::{detect_special, is_shiisanputaa, SpecialHand};
 use crate::tile::{Honor, Suit, Tile, TileKey};
-use crate::yaku::{eval_special_yaku, eval_yaku_standard};
-use crate::{MeldType, Riichi, ScoreRequest, ScoreResult, WinType};
+use crate::yaku::{apply_yakuman_policy, eval_special_yaku, eval_yaku_standard, YakuResult};
+use crate::{MeldType, Riichi, RobbedKan, ScoreRequest, ScoreResult, WinType, Wind};
 use std::collections::HashMap;
 
+/// 最も高い解釈で計算する（入力は score で validate_request 済みとする）
 pub fn score_best(req: &ScoreRequest) -> ScoreResult {
     // concealed tiles (hand + win)
     let hand: Vec<Tile> = req
@@ -67,26 +69,61 @@ pub fn score_best(req: &ScoreRequest) -> ScoreResult {
     // open info for fu/yaku
     let open_info = OpenInfo::from_melds(&req.melds);
 
+    // ドラ（分解に依らない）
+    let sanma = req.rules.sanma;
+    // 抜いた北も表示牌の対象（北がドラならその枚数分も乗る）
+    let mut dora_tiles = all_tiles.clone();
+    dora_tiles.extend((0..req.nukidora).map(|_| Tile::honor(Honor::North)));
+    // 赤ドラ・裏ドラ・槓ドラはルールで無効にできる
+    let rules = &req.rules;
+    let aka = if rules.aka_dora > 0 {
+        count_aka(&all_tiles)
+    } else {
+        0
+    };
+    let nuki = req.nukidora;
+    let mut dora = count_dora_from_indicators(&dora_tiles, &dora_inds, sanma);
+    if rules.kan_dora {
+        dora += count_dora_from_indicators(&dora_tiles, &kan_dora_inds, sanma);
+    }
+    let mut ura = 0;
+    if req.flags.riichi != Riichi::NONE && rules.ura_dora {
+        ura += count_dora_from_indicators(&dora_tiles, &ura_inds, sanma);
+        if rules.kan_dora {
+            ura += count_dora_from_indicators(&dora_tiles, &kan_ura_inds, sanma);
+        }
+    }
+
+    // 縛り（一定本場以上で必要な役の翻数、ドラ除く）
+    let min_yaku_han = req
+        .rules
+        .shibari
+        .filter(|sb| req.honba >= sb.honba)
+        .map(|sb| sb.han)
+        .unwrap_or(1);
+    let mut shibari_rejected = false;
+
     let mut best: Option<ScoreResult> = None;
 
     // A: special hands
     let sp_opt = detect_special(&tiles14, win_tile, has_any_melds)
-        .or_else(|| detect_special_fallback(&tiles14, win_tile, has_any_melds));
+        .or_else(|| detect_special_fallback(&tiles14, win_tile, has_any_melds))
+        .or_else(|| detect_shiisanputaa(req, &tiles14, has_any_melds));
+
+    // 暗槓の搶槓は国士無双のみ
+    if req.flags.robbed_kan == Some(RobbedKan::ANKAN)
+        && !matches!(sp_opt, Some(SpecialHand::Kokushi | SpecialHand::Kokushi13))
+    {
+        return error_result("暗槓への搶槓は国士無双でのみ和了できます".into());
+    }
 
     if let Some(sp) = sp_opt {
-        let yr = eval_special_yaku(sp);
-
-        let aka = count_aka(&all_tiles);
-        let dora = count_dora_from_indicators(&all_tiles, &dora_inds)
-            + count_dora_from_indicators(&all_tiles, &kan_dora_inds);
-        let ura = if req.flags.riichi != Riichi::NONE {
-            count_dora_from_indicators(&all_tiles, &ura_inds)
-                + count_dora_from_indicators(&all_tiles, &kan_ura_inds)
-        } else {
-            0
-        };
+        let mut yr = eval_special_yaku(sp);
+        merge_local_yaku(&mut yr, eval_local_yaku(None, &tiles14, win_tile, req));
+        let yakuman_policy = apply_yakuman_policy(&mut yr, &req.rules.yakuman);
+        let meets_shibari = yaku_han_ok(&yr, min_yaku_han);
 
-        let han = yr.han + dora + ura + aka;
+        let han = (yr.han + dora + ura + aka + nuki).max(yr.min_han);
         let yakuman = yr.yakuman;
 
         let mut yaku = yr.yaku;
@@ -99,10 +136,15 @@ pub fn score_best(req: &ScoreRequest) -> ScoreResult {
         if aka > 0 {
             yaku.push(format!("赤ドラ{}", aka));
         }
+        if nuki > 0 {
+            yaku.push(format!("抜きドラ{}", nuki));
+        }
 
-        let fu = match sp {
-            SpecialHand::Chiitoitsu => 25,
-            SpecialHand::Kokushi | SpecialHand::Kokushi13 => 0,
+        let (fu, fu_breakdown) = match sp {
+            SpecialHand::Chiitoitsu => (25, vec!["七対子25符".to_string()]),
+            SpecialHand::Kokushi | SpecialHand::Kokushi13 | SpecialHand::Shiisanputaa => {
+                (0, vec![])
+            }
         };
 
         let bd = calc_points(
@@ -113,34 +155,35 @@ pub fn score_best(req: &ScoreRequest) -> ScoreResult {
             req.dealer,
             req.honba,
             req.kyotaku,
+            &req.rules,
         );
 
-        best = Some(ScoreResult {
+        let cand = ScoreResult {
             total_points: bd.total_points,
             yakuman,
+            yakuman_policy,
             han,
             fu,
+            fu_breakdown,
             yaku,
             dora_han: dora,
             ura_dora_han: ura,
             aka_dora_han: aka,
-        });
+            nukidora_han: nuki,
+            chombo: false,
+        };
+        if meets_shibari {
+            best = Some(cand);
+        } else {
+            shibari_rejected = true;
+        }
     }
 
     // Standard hand patterns:
     // ✅ decompose.rs の仕様：pair は HandPattern.pair に別で保持される
     //    よって blocks_needed は「面子数」= 4 - 副露面子数
     if meld_block_count > 4 {
-        return ScoreResult {
-            total_points: 0,
-            yakuman: 0,
-            han: 0,
-            fu: 0,
-            yaku: vec!["副露が多すぎます（面子数が4を超えています）".into()],
-            dora_han: 0,
-            ura_dora_han: 0,
-            aka_dora_han: 0,
-        };
+        return error_result("副露が多すぎます（面子数が4を超えています）".into());
     }
 
     let blocks_needed = 4 - meld_block_count;
@@ -152,21 +195,15 @@ pub fn score_best(req: &ScoreRequest) -> ScoreResult {
         if let Some(b) = best {
             return b;
         }
+        if shibari_rejected {
+            return error_result(shibari_message(min_yaku_han, req.honba));
+        }
 
         // ✅ special でも拾えず、標準形でも分解できない場合だけエラー
-        return ScoreResult {
-            total_points: 0,
-            yakuman: 0,
-            han: 0,
-            fu: 0,
-            yaku: vec![format!(
-                "分解できませんでした: tiles14={:?}, blocks_needed={}, meld_blocks={}",
-                tiles14, blocks_needed, meld_block_count
-            )],
-            dora_han: 0,
-            ura_dora_han: 0,
-            aka_dora_han: 0,
-        };
+        return error_result(format!(
+            "分解できませんでした: tiles14={:?}, blocks_needed={}, meld_blocks={}",
+            tiles14, blocks_needed, meld_block_count
+        ));
     }
 
     for mut p in patterns.drain(..) {
@@ -180,7 +217,7 @@ pub fn score_best(req: &ScoreRequest) -> ScoreResult {
         p.pair_key = p.extract_pair_key();
         p.open_info = Some(open_info.clone());
 
-        let yr = eval_yaku_standard(
+        let mut yr = eval_yaku_standard(
             &p,
             &tiles14,
             win_tile,
@@ -188,43 +225,46 @@ pub fn score_best(req: &ScoreRequest) -> ScoreResult {
             req.round_wind,
             req.seat_wind,
             req.flags.riichi,
-            req.flags.ippatsu,
+            req.flags.ippatsu && req.rules.ippatsu,
             req.flags.rinshan,
             req.flags.chankan,
             req.flags.haitei,
             req.flags.houtei,
+            req.rules.kuitan,
         );
 
+        merge_local_yaku(&mut yr, eval_local_yaku(Some(&p), &tiles14, win_tile, req));
+        let yakuman_policy = apply_yakuman_policy(&mut yr, &req.rules.yakuman);
+
         // yakuなしは無効
-        if yr.yakuman == 0 && yr.han == 0 {
+        if yr.yakuman == 0 && yr.han == 0 && yr.min_han == 0 {
+            continue;
+        }
+        if !yaku_han_ok(&yr, min_yaku_han) {
+            shibari_rejected = true;
             continue;
         }
 
-        let (mut fu, _wt) = calc_fu(
+        let fr = calc_fu(
             &p,
             win_tile,
             req.win_type,
             p.menzen,
             req.round_wind,
             req.seat_wind,
+            req.flags.rinshan,
+            &req.rules,
         );
+        let mut fu = fr.fu;
+        let mut fu_breakdown = fr.breakdown;
 
         // 平和ツモは 20符
         if yr.yaku.iter().any(|y| y == "平和") && req.win_type == WinType::TSUMO {
             fu = 20;
+            fu_breakdown = vec!["平和ツモ20符".to_string()];
         }
 
-        let aka = count_aka(&all_tiles);
-        let dora = count_dora_from_indicators(&all_tiles, &dora_inds)
-            + count_dora_from_indicators(&all_tiles, &kan_dora_inds);
-        let ura = if req.flags.riichi != Riichi::NONE {
-            count_dora_from_indicators(&all_tiles, &ura_inds)
-                + count_dora_from_indicators(&all_tiles, &kan_ura_inds)
-        } else {
-            0
-        };
-
-        let han = yr.han + dora + ura + aka;
+        let han = (yr.han + dora + ura + aka + nuki).max(yr.min_han);
         let yakuman = yr.yakuman;
 
         let mut yaku = yr.yaku;
@@ -237,6 +277,9 @@ pub fn score_best(req: &ScoreRequest) -> ScoreResult {
         if aka > 0 {
             yaku.push(format!("赤ドラ{}", aka));
         }
+        if nuki > 0 {
+            yaku.push(format!("抜きドラ{}", nuki));
+        }
 
         let bd = calc_points(
             fu,
@@ -246,17 +289,22 @@ pub fn score_best(req: &ScoreRequest) -> ScoreResult {
             req.dealer,
             req.honba,
             req.kyotaku,
+            &req.rules,
         );
 
         let cand = ScoreResult {
             total_points: bd.total_points,
             yakuman,
+            yakuman_policy,
             han,
             fu,
+            fu_breakdown,
             yaku,
             dora_han: dora,
             ura_dora_han: ura,
             aka_dora_han: aka,
+            nukidora_han: nuki,
+            chombo: false,
         };
 
         best = match best {
@@ -269,16 +317,184 @@ pub fn score_best(req: &ScoreRequest) -> ScoreResult {
         };
     }
 
-    best.unwrap_or(ScoreResult {
+    best.unwrap_or_else(|| {
+        if shibari_rejected {
+            error_result(shibari_message(min_yaku_han, req.honba))
+        } else {
+            error_result("役なし（和了不可）".into())
+        }
+    })
+}
+
+/// 役の翻数（ドラ除く）が縛りを満たすか。役満・満貫役は常に満たす
+fn yaku_han_ok(yr: &YakuResult, min_yaku_han: u32) -> bool {
+    yr.yakuman > 0 || yr.han.max(yr.min_han) >= min_yaku_han
+}
+
+fn shibari_message(min_yaku_han: u32, honba: u32) -> String {
+    format!(
+        "{}翻縛り未達（{}本場：ドラを除く役が{}翻以上必要）",
+        min_yaku_han, honba, min_yaku_han
+    )
+}
+
+/// 和了不可・入力エラー時の結果（理由は yaku に入れる）
+pub fn error_result(msg: String) -> ScoreResult {
+    ScoreResult {
         total_points: 0,
         yakuman: 0,
+        yakuman_policy: None,
         han: 0,
         fu: 0,
-        yaku: vec!["役なし（和了不可）".into()],
+        fu_breakdown: vec![],
+        yaku: vec![msg],
         dora_han: 0,
         ura_dora_han: 0,
         aka_dora_han: 0,
-    })
+        nukidora_han: 0,
+        chombo: false,
+    }
+}
+
+/// 牌コードと三麻の制限（2m〜8m なし・チーなし・北家なし）を確認する
+pub fn validate_request(req: &ScoreRequest) -> Result<(), String> {
+    let sanma = req.rules.sanma;
+
+    let mut codes: Vec<&String> = req.hand_tiles.iter().collect();
+    codes.push(&req.win_tile);
+    for m in &req.melds {
+        codes.extend(m.tiles.iter());
+    }
+    let hand_len = codes.len();
+    codes.extend(req.dora_indicators.iter());
+    codes.extend(req.kan_dora_indicators.iter());
+    codes.extend(req.ura_indicators.iter());
+    codes.extend(req.kan_ura_indicators.iter());
+
+    let mut hand = vec![];
+    for (i, code) in codes.into_iter().enumerate() {
+        let t = Tile::from_code(code)?;
+        if sanma && t.suit == Suit::Man && (2..=8).contains(&t.num) {
+            return Err(format!("三麻では使わない牌です: {}", code));
+        }
+        if i < hand_len {
+            hand.push(t);
+        }
+    }
+
+    let aka = count_aka(&hand);
+    if req.rules.aka_dora > 0 && aka > req.rules.aka_dora {
+        return Err(format!(
+            "赤ドラが{}枚あります（このルールでは{}枚まで）",
+            aka, req.rules.aka_dora
+        ));
+    }
+
+    if sanma {
+        if req.melds.iter().any(|m| m.meld_type == MeldType::CHI) {
+            return Err("三麻ではチーできません".into());
+        }
+        if req.seat_wind == Wind::N {
+            return Err("三麻に北家はありません".into());
+        }
+    } else if req.nukidora > 0 && !req.rules.yonma_nukidora {
+        return Err("このルールでは抜きドラは使えません".into());
+    }
+
+    // 北は4枚しかない（手牌・副露にある北の残りしか抜けない）
+    let north = req
+        .hand_tiles
+        .iter()
+        .chain(std::iter::once(&req.win_tile))
+        .chain(req.melds.iter().flat_map(|m| m.tiles.iter()))
+        .filter(|c| c.as_str() == "N")
+        .count() as u32;
+    if req.nukidora + north > 4 {
+        return Err(format!(
+            "抜きドラが{}枚あります（手牌・副露に北が{}枚あるので{}枚まで）",
+            req.nukidora,
+            north,
+            4u32.saturating_sub(north)
+        ));
+    }
+
+    let ym = &req.rules.yakuman;
+    if !(1..=6).contains(&ym.cap) {
+        return Err(format!("役満の上限倍率は1〜6です: {}", ym.cap));
+    }
+    if let Some((name, _)) = ym.multipliers.iter().find(|(_, &m)| !(1..=6).contains(&m)) {
+        return Err(format!("役満の倍率は1〜6です: {}", name));
+    }
+
+    validate_flags(req)
+}
+
+/// 状況役フラグの組み合わせを確認する
+fn validate_flags(req: &ScoreRequest) -> Result<(), String> {
+    let f = &req.flags;
+    let tsumo = req.win_type == WinType::TSUMO;
+    let has_open_melds = !is_menzen_strict(req);
+    let has_kan = req
+        .melds
+        .iter()
+        .any(|m| matches!(m.meld_type, MeldType::MINKAN | MeldType::ANKAN));
+
+    if f.ippatsu && f.riichi == Riichi::NONE {
+        return Err("一発は立直していないと付きません".into());
+    }
+    if f.riichi != Riichi::NONE && has_open_melds {
+        return Err("副露（チー/ポン/明槓）していると立直できません".into());
+    }
+    if f.haitei && !tsumo {
+        return Err("海底摸月はツモ和了のみです（ロンは河底撈魚）".into());
+    }
+    if f.houtei && tsumo {
+        return Err("河底撈魚はロン和了のみです（ツモは海底摸月）".into());
+    }
+    if f.rinshan && !tsumo {
+        return Err("嶺上開花はツモ和了のみです".into());
+    }
+    if f.rinshan && !has_kan {
+        return Err("嶺上開花には槓子（明槓/暗槓）が必要です".into());
+    }
+    if f.rinshan && f.haitei {
+        return Err("嶺上牌での和了は海底摸月になりません".into());
+    }
+    if f.rinshan && f.ippatsu {
+        return Err("槓をすると一発は消えるので、嶺上開花と一発は同時に成立しません".into());
+    }
+    if f.chankan && tsumo {
+        return Err("搶槓はロン和了のみです".into());
+    }
+    if f.chankan && f.houtei {
+        return Err("最後の牌の後は槓できないので、搶槓と河底撈魚は同時に成立しません".into());
+    }
+    if f.robbed_kan.is_some() && !f.chankan {
+        return Err("奪った槓の種類は搶槓のときのみ指定できます".into());
+    }
+    if f.robbed_kan == Some(RobbedKan::ANKAN) && !req.rules.ankan_chankan_kokushi {
+        return Err("このルールでは暗槓は搶槓できません".into());
+    }
+    if f.tenhou && f.chihou {
+        return Err("天和と地和は同時に成立しません".into());
+    }
+    if (f.tenhou || f.chihou || f.renhou) && !req.melds.is_empty() {
+        return Err("天和/地和/人和は副露（暗槓を含む）があると成立しません".into());
+    }
+    if f.tenhou && (!tsumo || !req.dealer) {
+        return Err("天和は親のツモ和了のみです".into());
+    }
+    if f.chihou && (!tsumo || req.dealer) {
+        return Err("地和は子のツモ和了のみです".into());
+    }
+    if f.renhou && (tsumo || req.dealer) {
+        return Err("人和は子のロン和了のみです".into());
+    }
+    if f.open_riichi && f.riichi == Riichi::NONE {
+        return Err("オープン立直は立直していないと付きません".into());
+    }
+
+    Ok(())
 }
 
 fn is_menzen_strict(req: &ScoreRequest) -> bool {
@@ -384,9 +600,23 @@ fn detect_special_fallback(
     }
 }
 
+/// 十三不塔（ローカル役）：第一ツモでの和了に限る
+fn detect_shiisanputaa(
+    req: &ScoreRequest,
+    tiles14: &[Tile],
+    has_calls: bool,
+) -> Option<SpecialHand> {
+    let first_draw = req.flags.tenhou || req.flags.chihou;
+    (req.rules.local_yaku.shiisanputaa
+        && first_draw
+        && req.win_type == WinType::TSUMO
+        && is_shiisanputaa(tiles14, has_calls))
+    .then_some(SpecialHand::Shiisanputaa)
+}
+
 // ===== ソート用のローカルヘルパー =====
 
-fn sort_tiles(v: &mut Vec<Tile>) {
+fn sort_tiles(v: &mut [Tile]) {
     v.sort_by_key(|t| (rank_suit(t.suit), rank_honor(t.honor), t.num, t.red));
 }
 
@@ -0,0 +1,152 @@
+use crate::round::RoundEvent;
+use crate::{Flags, MeldType, Riichi, RobbedKan, ScoreRequest, WinType, Wind};
+
+/// 局の記録から和了時の状況役フラグを決める
+/// - events: 局の最初（START）から和了直前までの記録
+/// - ツモなら最後の記録は winner の DRAW、ロンなら他家の DISCARD（搶槓は KAN）
+/// - オープン立直は記録から分からないので常に false
+pub fn derive_flags(
+    events: &[RoundEvent],
+    winner: usize,
+    win_type: WinType,
+) -> Result<Flags, String> {
+    let Some(RoundEvent::START { dealer, wall }) = events.first() else {
+        return Err("局の記録が START から始まっていません".into());
+    };
+    let (dealer, mut remaining) = (*dealer, *wall);
+    let tsumo = win_type == WinType::TSUMO;
+
+    let mut riichi = Riichi::NONE;
+    let mut ippatsu = false;
+    // 誰かが鳴く・槓するまでの第一巡
+    let mut interrupted = false;
+    let mut winner_drew = false;
+    let mut winner_discarded = false;
+
+    // 搶槓は槓の成立前なので、奪われた槓は数えない
+    let last = events.last().unwrap();
+    let body = match (win_type, last) {
+        (WinType::RON, RoundEvent::KAN { .. }) => &events[..events.len() - 1],
+        _ => events,
+    };
+
+    for e in body {
+        match e {
+            RoundEvent::START { .. } | RoundEvent::DORA { .. } => {}
+            RoundEvent::DRAW { seat, rinshan, .. } => {
+                if !rinshan {
+                    remaining = remaining.saturating_sub(1);
+                }
+                if *seat == winner {
+                    winner_drew = true;
+                }
+            }
+            RoundEvent::DISCARD {
+                seat,
+                riichi: declared,
+                ..
+            } => {
+                if *seat != winner {
+                    continue;
+                }
+                ippatsu = false;
+                if *declared {
+                    if riichi != Riichi::NONE {
+                        return Err("立直を2回宣言しています".into());
+                    }
+                    riichi = if !winner_discarded && !interrupted {
+                        Riichi::DOUBLE
+                    } else {
+                        Riichi::RIICHI
+                    };
+                    ippatsu = true;
+                }
+                winner_discarded = true;
+            }
+            RoundEvent::CALL { meld, .. } => {
+                interrupted = true;
+                ippatsu = false;
+                if meld.meld_type == MeldType::MINKAN {
+                    remaining = remaining.saturating_sub(1);
+                }
+            }
+            RoundEvent::KAN { .. } => {
+                interrupted = true;
+                ippatsu = false;
+                remaining = remaining.saturating_sub(1);
+            }
+        }
+    }
+
+    let (rinshan, robbed_kan, riichi_tile) = match (win_type, last) {
+        (WinType::TSUMO, RoundEvent::DRAW { seat, rinshan, .. }) if *seat == winner => {
+            (*rinshan, None, false)
+        }
+        (WinType::RON, RoundEvent::DISCARD { seat, riichi, .. }) if *seat != winner => {
+            (false, None, *riichi)
+        }
+        (WinType::RON, RoundEvent::KAN { seat, meld }) if *seat != winner => {
+            let robbed = if meld.meld_type == MeldType::ANKAN {
+                RobbedKan::ANKAN
+            } else {
+                RobbedKan::KAKAN
+            };
+            (false, Some(robbed), false)
+        }
+        _ => {
+            return Err(match win_type {
+                WinType::TSUMO => "ツモ和了の記録が和了者のツモで終わっていません".into(),
+                WinType::RON => "ロン和了の記録が他家の打牌か槓で終わっていません".into(),
+            })
+        }
+    };
+
+    let first = !interrupted && !winner_discarded;
+    let is_dealer = winner == dealer;
+
+    Ok(Flags {
+        riichi,
+        ippatsu,
+        rinshan,
+        chankan: robbed_kan.is_some(),
+        robbed_kan,
+        haitei: tsumo && remaining == 0 && !rinshan,
+        houtei: !tsumo && remaining == 0 && robbed_kan.is_none(),
+        tenhou: tsumo && first && is_dealer,
+        chihou: tsumo && first && !is_dealer,
+        renhou: !tsumo && first && !winner_drew && !is_dealer,
+        open_riichi: false,
+        tsubame_gaeshi: riichi_tile,
+    })
+}
+
+/// events 付きのリクエストなら、flags を記録から決め直したものを返す
+/// - 和了者の席は START の親と seat_wind から決める
+/// - オープン立直だけはリクエストの指定を使う
+pub fn with_event_flags(req: &ScoreRequest) -> Result<Option<ScoreRequest>, String> {
+    let Some(events) = &req.events else {
+        return Ok(None);
+    };
+    let winner = event_seat(events, req)?;
+
+    let mut flags = derive_flags(events, winner, req.win_type)?;
+    flags.open_riichi = req.flags.open_riichi && flags.riichi != Riichi::NONE;
+
+    let mut derived = req.clone();
+    derived.flags = flags;
+    Ok(Some(derived))
+}
+
+/// 記録の START の親と seat_wind から和了者の席を決める
+pub(crate) fn event_seat(events: &[RoundEvent], req: &ScoreRequest) -> Result<usize, String> {
+    let Some(RoundEvent::START { dealer, .. }) = events.first() else {
+        return Err("局の記録が START から始まっていません".into());
+    };
+    let offset = match req.seat_wind {
+        Wind::E => 0,
+        Wind::S => 1,
+        Wind::W => 2,
+        Wind::N => 3,
+    };
+    Ok((dealer + offset) % req.rules.players())
+}
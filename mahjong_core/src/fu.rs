@@ -1,4 +1,5 @@
 use crate::decompose::{Block, HandPattern};
+use crate::rules::{OpenPinfuFu, RuleSet};
 use crate::tile::{Honor, Suit, Tile, TileKey};
 use crate::{WinType, Wind};
 
@@ -22,6 +23,15 @@ pub fn detect_wait_type(pattern: &HandPattern, win_tile: Tile) -> WaitType {
     detect_wait_info(pattern, win_tile, WinType::TSUMO).wait_type
 }
 
+/// 符計算の結果
+#[derive(Debug, Clone)]
+pub struct FuResult {
+    pub fu: u32,
+    /// 内訳（例: "副底20符", "ツモ2符", "切り上げ 22→30符"）
+    pub breakdown: Vec<String>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn calc_fu(
     pattern: &HandPattern,
     win_tile: Tile,
@@ -29,32 +39,54 @@ pub fn calc_fu(
     menzen: bool,
     round_wind: Wind,
     seat_wind: Wind,
-) -> (u32, WaitType) {
+    rinshan: bool,
+    rules: &RuleSet,
+) -> FuResult {
     // 待ち形（+ シャンポンロンで完成した刻子キー）
     let wi = detect_wait_info(pattern, win_tile, win_type);
+    let pinfu_like = is_pinfu_shape(pattern, round_wind, seat_wind, wi.wait_type);
+    let mut breakdown = vec![];
 
     // ---- 基本符 ----
     // 基本は 20符
     let mut fu: u32 = 20;
+    breakdown.push("副底20符".to_string());
 
-    // ツモ 2符
+    // ツモ 2符（嶺上開花・喰い平和形はルール次第）
     if win_type == WinType::TSUMO {
-        fu += 2;
+        if rinshan && !rules.rinshan_tsumo_fu {
+            breakdown.push("嶺上開花ツモ符なし".to_string());
+        } else if !menzen && pinfu_like && rules.open_pinfu_fu == OpenPinfuFu::FIXED {
+            breakdown.push("喰い平和形ツモ符なし".to_string());
+        } else {
+            fu += 2;
+            breakdown.push("ツモ2符".to_string());
+        }
     }
 
     // 門前ロン 10符
     if win_type == WinType::RON && menzen {
         fu += 10;
+        breakdown.push("門前加符10符".to_string());
     }
 
     // 雀頭役牌 2符（場風/自風/三元）
     if let Some(pair_key) = pattern.pair_key {
-        fu += pair_fu(pair_key, round_wind, seat_wind);
+        let f = pair_fu(pair_key, round_wind, seat_wind);
+        if f > 0 {
+            fu += f;
+            breakdown.push(format!("雀頭{}符", f));
+        }
     }
 
     // 面子符
+    let mut meld_fu = 0;
     for b in &pattern.blocks {
-        fu += block_fu(b, pattern.open_info.as_ref(), wi.ron_completed_triplet);
+        meld_fu += block_fu(b, pattern.open_info.as_ref(), wi.ron_completed_triplet);
+    }
+    if meld_fu > 0 {
+        fu += meld_fu;
+        breakdown.push(format!("面子{}符", meld_fu));
     }
 
     // 待ち符（嵌張・辺張・単騎 = +2）
@@ -63,6 +95,7 @@ pub fn calc_fu(
         WaitType::Kanchan | WaitType::Penchan | WaitType::Tanki
     ) {
         fu += 2;
+        breakdown.push("待ち2符".to_string());
     }
 
     // ---- 例外と最低符 ----
@@ -79,21 +112,27 @@ pub fn calc_fu(
     //
     // ※この条件を満たす場合だけ 20符を許可。そうでなければ最低30符。
     if fu == 20 {
-        let pinfu_like = is_pinfu_shape(pattern, round_wind, seat_wind, wi.wait_type);
         let allow_20 = menzen && win_type == WinType::TSUMO && pinfu_like;
         if !allow_20 {
             fu = 30;
+            breakdown.push("最低30符".to_string());
         }
     }
 
     // 10符単位切り上げ（25符固定の七対子は engine 側で別扱いなのでここでは不要）
-    fu = round_up_10(fu);
+    let rounded = round_up_10(fu);
+    if rounded != fu {
+        breakdown.push(format!("切り上げ {}→{}符", fu, rounded));
+    }
 
-    (fu, wi.wait_type)
+    FuResult {
+        fu: rounded,
+        breakdown,
+    }
 }
 
 fn round_up_10(x: u32) -> u32 {
-    ((x + 9) / 10) * 10
+    x.div_ceil(10) * 10
 }
 
 fn pair_fu(pair: TileKey, round_wind: Wind, seat_wind: Wind) -> u32 {
@@ -0,0 +1,111 @@
+use crate::flags::event_seat;
+use crate::round::RoundEvent;
+use crate::tile::{Tile, TileKey};
+use crate::wait::winning_tiles;
+use crate::{MeldType, Riichi, ScoreRequest, WinType};
+use serde::{Deserialize, Serialize};
+
+/// フリテンの種類
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Furiten {
+    /// 自分の捨て牌に待ち牌がある
+    PERMANENT,
+    /// 直近の自分の打牌より後に待ち牌を見逃した（同巡内）
+    TEMPORARY,
+    /// 立直後に待ち牌を見逃した
+    RIICHI,
+}
+
+impl Furiten {
+    pub fn label(self) -> &'static str {
+        match self {
+            Furiten::PERMANENT => "フリテン（捨て牌に待ち牌あり）",
+            Furiten::TEMPORARY => "同巡内フリテン",
+            Furiten::RIICHI => "立直後の見逃しフリテン",
+        }
+    }
+}
+
+/// ロン和了者の河と見逃した牌（和了牌は含めない）
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct FuritenInfo {
+    /// 自分の捨て牌（鳴かれた牌を含む）
+    pub discards: Vec<Tile>,
+    /// 直近の自分の打牌より後に他家が切った（加槓した）牌
+    #[serde(default)]
+    pub passed: Vec<Tile>,
+    /// 立直宣言より後に他家が切った（加槓した）牌
+    #[serde(default)]
+    pub riichi_passed: Vec<Tile>,
+}
+
+impl FuritenInfo {
+    /// 局の記録から作る（最後の記録は和了牌の打牌・加槓として数えない）
+    pub fn from_events(events: &[RoundEvent], seat: usize) -> FuritenInfo {
+        let mut info = FuritenInfo::default();
+        let mut riichi = false;
+        let body = &events[..events.len().saturating_sub(1)];
+        for e in body {
+            let tile = match e {
+                RoundEvent::DISCARD {
+                    seat: s,
+                    tile,
+                    riichi: declared,
+                } if *s == seat => {
+                    info.discards.push(*tile);
+                    info.passed.clear();
+                    riichi |= *declared;
+                    continue;
+                }
+                RoundEvent::DISCARD { tile, .. } => *tile,
+                // 加槓は搶槓できる
+                RoundEvent::KAN { seat: s, meld }
+                    if *s != seat && meld.meld_type == MeldType::MINKAN =>
+                {
+                    Tile::from_code(meld.tiles.last().unwrap()).unwrap()
+                }
+                _ => continue,
+            };
+            info.passed.push(tile);
+            if riichi {
+                info.riichi_passed.push(tile);
+            }
+        }
+        info
+    }
+
+    /// 13枚形（副露を除く）の待ちからフリテンを判定する
+    /// - riichi: 立直しているか（riichi_passed を見るか）
+    pub fn check(&self, hand: &[Tile], meld_count: usize, riichi: bool) -> Option<Furiten> {
+        let waits = winning_tiles(hand, meld_count);
+        let hits = |tiles: &[Tile]| tiles.iter().any(|t| waits.contains(&TileKey::from_tile(t)));
+        if hits(&self.discards) {
+            Some(Furiten::PERMANENT)
+        } else if riichi && hits(&self.riichi_passed) {
+            Some(Furiten::RIICHI)
+        } else if hits(&self.passed) {
+            Some(Furiten::TEMPORARY)
+        } else {
+            None
+        }
+    }
+}
+
+/// ロン和了のフリテン判定（furiten か events があるときのみ）
+pub fn ron_furiten(req: &ScoreRequest) -> Result<Option<Furiten>, String> {
+    if req.win_type != WinType::RON {
+        return Ok(None);
+    }
+    let info = match (&req.furiten, &req.events) {
+        (Some(info), _) => info.clone(),
+        (None, Some(events)) => FuritenInfo::from_events(events, event_seat(events, req)?),
+        (None, None) => return Ok(None),
+    };
+    let hand = req
+        .hand_tiles
+        .iter()
+        .map(|c| Tile::from_code(c))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(info.check(&hand, req.melds.len(), req.flags.riichi != Riichi::NONE))
+}
@@ -0,0 +1,334 @@
+use crate::points::{calc_points, chombo_deltas, draw_deltas, payment_deltas};
+use crate::rules::{ChomboPayment, GameLength, RuleSet};
+use crate::settlement::{rank_order, settle_final, FinalStanding};
+use crate::{WinType, Wind};
+use serde::Serialize;
+
+/// 終局理由
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum GameEndReason {
+    /// 誰かの持ち点が0未満になった（トビ）
+    TOBI,
+    /// オーラス親の和了止め
+    AGARIYAME,
+    /// オーラス親の聴牌止め
+    TENPAIYAME,
+    /// 規定局（東4/南4）の終了
+    LAST,
+    /// 延長戦で extension_target に到達（サドンデス）
+    SUDDENDEATH,
+    /// 延長戦の最終局（西4など）の終了
+    EXTENSIONLAST,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HandOutcome {
+    WIN,
+    DRAW,
+    /// 錯和・不正立直などの罰符
+    CHOMBO,
+}
+
+/// 1局分の記録
+#[derive(Debug, Clone, Serialize)]
+pub struct HandRecord {
+    pub round_wind: Wind,
+    pub kyoku: u32,
+    pub honba: u32,
+    pub outcome: HandOutcome,
+    /// 席ごとの点数移動（供託の受け取りを含む）
+    pub deltas: Vec<i32>,
+    /// チョンボをした席（CHOMBO のときのみ）
+    pub offender: Option<usize>,
+}
+
+/// 半荘（東風）通しの点数台帳
+#[derive(Debug, Clone, Serialize)]
+pub struct GameLedger {
+    pub rules: RuleSet,
+    /// 起家から順の持ち点
+    pub points: Vec<i32>,
+    pub round_wind: Wind,
+    /// 1..=人数（親 = kyoku - 1）
+    pub kyoku: u32,
+    pub honba: u32,
+    pub kyotaku: u32,
+    /// この局で立直した席（チョンボで局が無効になったら供託を返す）
+    pub hand_riichi: Vec<usize>,
+    /// 終局時に最終スコアから差し引く点数（席ごと、ChomboPayment::DEDUCT）
+    pub penalties: Vec<i32>,
+    pub records: Vec<HandRecord>,
+    pub end: Option<GameEndReason>,
+}
+
+impl GameLedger {
+    /// ウマの数が人数と合わないルール（三麻で4人分のウマなど）は終局精算ができないので拒否する
+    pub fn new(rules: RuleSet) -> Result<Self, String> {
+        let players = rules.players();
+        if rules.uma.len() != players {
+            return Err(format!(
+                "ウマの数({})が人数({})と一致しません",
+                rules.uma.len(),
+                players
+            ));
+        }
+        Ok(GameLedger {
+            points: vec![rules.start_points; players],
+            rules,
+            round_wind: Wind::E,
+            kyoku: 1,
+            honba: 0,
+            kyotaku: 0,
+            hand_riichi: vec![],
+            penalties: vec![0; players],
+            records: vec![],
+            end: None,
+        })
+    }
+
+    pub fn players(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn dealer(&self) -> usize {
+        (self.kyoku - 1) as usize
+    }
+
+    /// 立直宣言：1000点を供託に出す
+    pub fn declare_riichi(&mut self, seat: usize) -> Result<(), String> {
+        self.check_open(seat)?;
+        self.points[seat] -= 1000;
+        self.kyotaku += 1;
+        self.hand_riichi.push(seat);
+        Ok(())
+    }
+
+    /// 和了の記録（loser: None ならツモ）
+    pub fn record_win(
+        &mut self,
+        winner: usize,
+        loser: Option<usize>,
+        han: u32,
+        fu: u32,
+        yakuman: u32,
+    ) -> Result<(), String> {
+        self.check_open(winner)?;
+        if let Some(l) = loser {
+            self.check_open(l)?;
+            if l == winner {
+                return Err("放銃者と和了者が同じです".into());
+            }
+        }
+
+        let dealer = self.dealer();
+        let win_type = if loser.is_some() {
+            WinType::RON
+        } else {
+            WinType::TSUMO
+        };
+        let bd = calc_points(
+            fu,
+            han,
+            yakuman,
+            win_type,
+            winner == dealer,
+            self.honba,
+            self.kyotaku,
+            &self.rules,
+        );
+
+        let deltas = payment_deltas(&bd, self.players(), dealer, winner, loser);
+        self.kyotaku = 0;
+
+        self.finish_hand(HandOutcome::WIN, deltas, winner == dealer);
+        Ok(())
+    }
+
+    /// 荒牌流局の記録（席ごとの聴牌/不聴）
+    pub fn record_draw(&mut self, tenpai: &[bool]) -> Result<(), String> {
+        if self.end.is_some() {
+            return Err("対局は終了しています".into());
+        }
+        if tenpai.len() != self.players() {
+            return Err(format!(
+                "聴牌情報の数({})が人数({})と一致しません",
+                tenpai.len(),
+                self.players()
+            ));
+        }
+
+        let deltas = draw_deltas(tenpai);
+        let dealer_tenpai = tenpai[self.dealer()];
+        self.finish_hand(HandOutcome::DRAW, deltas, dealer_tenpai);
+        Ok(())
+    }
+
+    /// チョンボの記録
+    /// - この局の立直棒は返し、rules.chombo_payment に従って罰符を精算する
+    /// - rules.chombo_replay なら同じ局・同じ本場をやり直す
+    pub fn record_chombo(&mut self, offender: usize) -> Result<(), String> {
+        self.check_open(offender)?;
+
+        let mut deltas = match self.rules.chombo_payment {
+            ChomboPayment::PAY => {
+                chombo_deltas(self.players(), self.dealer(), offender, &self.rules)
+            }
+            ChomboPayment::DEDUCT => {
+                self.penalties[offender] += self.rules.chombo_deduction;
+                vec![0; self.players()]
+            }
+        };
+        for seat in self.hand_riichi.drain(..) {
+            deltas[seat] += 1000;
+            self.kyotaku -= 1;
+        }
+
+        for (p, d) in self.points.iter_mut().zip(&deltas) {
+            *p += d;
+        }
+        self.records.push(HandRecord {
+            round_wind: self.round_wind,
+            kyoku: self.kyoku,
+            honba: self.honba,
+            outcome: HandOutcome::CHOMBO,
+            deltas,
+            offender: Some(offender),
+        });
+
+        if self.rules.tobi && self.points.iter().any(|&p| p < 0) {
+            self.end = Some(GameEndReason::TOBI);
+        } else if !self.rules.chombo_replay {
+            self.end = self.check_end(HandOutcome::CHOMBO, false);
+            if self.end.is_none() {
+                self.advance();
+            }
+        }
+        Ok(())
+    }
+
+    /// 終局後の順位・ウマオカ精算（チョンボの差し引きを含む）
+    pub fn final_standings(&self) -> Result<Vec<FinalStanding>, String> {
+        let mut standings = settle_final(&self.points, self.kyotaku, &self.rules)?;
+        for s in &mut standings {
+            s.score -= self.penalties[s.seat] as f64 / 1000.0;
+        }
+        Ok(standings)
+    }
+
+    fn check_open(&self, seat: usize) -> Result<(), String> {
+        if self.end.is_some() {
+            return Err("対局は終了しています".into());
+        }
+        if seat >= self.players() {
+            return Err(format!("席番号が不正です: {}", seat));
+        }
+        Ok(())
+    }
+
+    fn finish_hand(&mut self, outcome: HandOutcome, deltas: Vec<i32>, renchan: bool) {
+        for (p, d) in self.points.iter_mut().zip(&deltas) {
+            *p += d;
+        }
+        self.records.push(HandRecord {
+            round_wind: self.round_wind,
+            kyoku: self.kyoku,
+            honba: self.honba,
+            outcome,
+            deltas,
+            offender: None,
+        });
+        self.hand_riichi.clear();
+
+        self.end = self.check_end(outcome, renchan);
+
+        // 連荘なら積み棒+1、流局でも積み棒+1、子の和了なら0本場へ
+        if renchan || outcome == HandOutcome::DRAW {
+            self.honba += 1;
+        } else {
+            self.honba = 0;
+        }
+        if !renchan && self.end.is_none() {
+            self.advance();
+        }
+    }
+
+    fn check_end(&self, outcome: HandOutcome, renchan: bool) -> Option<GameEndReason> {
+        if self.rules.tobi && self.points.iter().any(|&p| p < 0) {
+            return Some(GameEndReason::TOBI);
+        }
+
+        let regular_last = last_wind(self.rules.game_length);
+        let in_extension = wind_index(self.round_wind) > wind_index(regular_last);
+        let reached_target = self
+            .points
+            .iter()
+            .any(|&p| p >= self.rules.extension_target);
+
+        if in_extension && reached_target {
+            return Some(GameEndReason::SUDDENDEATH);
+        }
+
+        let all_last = self.kyoku as usize == self.players()
+            && (self.round_wind == regular_last || in_extension);
+        if !all_last {
+            return None;
+        }
+
+        if renchan {
+            // 親が単独トップ（同点は席順で上位）なら止められる
+            let dealer = self.dealer();
+            let top = rank_order(&self.points)[0];
+            let may_stop = top == dealer
+                && (!self.rules.extension || self.points[dealer] >= self.rules.extension_target);
+            return match outcome {
+                HandOutcome::WIN if may_stop && self.rules.agari_yame => {
+                    Some(GameEndReason::AGARIYAME)
+                }
+                HandOutcome::DRAW if may_stop && self.rules.tenpai_yame => {
+                    Some(GameEndReason::TENPAIYAME)
+                }
+                _ => None,
+            };
+        }
+
+        if in_extension {
+            Some(GameEndReason::EXTENSIONLAST)
+        } else if !self.rules.extension || reached_target {
+            Some(GameEndReason::LAST)
+        } else {
+            None
+        }
+    }
+
+    fn advance(&mut self) {
+        if (self.kyoku as usize) < self.players() {
+            self.kyoku += 1;
+            return;
+        }
+        self.kyoku = 1;
+        self.round_wind = match self.round_wind {
+            Wind::E => Wind::S,
+            Wind::S => Wind::W,
+            Wind::W => Wind::N,
+            Wind::N => Wind::E,
+        };
+    }
+}
+
+fn last_wind(length: GameLength) -> Wind {
+    match length {
+        GameLength::TONPUU => Wind::E,
+        GameLength::HANCHAN => Wind::S,
+    }
+}
+
+fn wind_index(w: Wind) -> u8 {
+    match w {
+        Wind::E => 0,
+        Wind::S => 1,
+        Wind::W => 2,
+        Wind::N => 3,
+    }
+}
@@ -16,9 +16,10 @@ pub enum WinType {
     TSUMO,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum Riichi {
+    #[default]
     NONE,
     RIICHI,
     DOUBLE,
@@ -33,23 +34,45 @@ pub enum MeldType {
     ANKAN,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Meld {
     #[serde(rename = "type")]
     pub meld_type: MeldType,
     pub tiles: Vec<String>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// 搶槓で奪った槓の種類
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum RobbedKan {
+    /// 加槓（通常の搶槓）
+    KAKAN,
+    /// 暗槓（ルールにより国士無双のみ）
+    ANKAN,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Flags {
     pub riichi: Riichi,
     pub ippatsu: bool,
     pub rinshan: bool,
     pub chankan: bool,
+    /// 搶槓のとき奪った槓（省略時は加槓）
+    #[serde(default)]
+    pub robbed_kan: Option<RobbedKan>,
     pub haitei: bool,
     pub houtei: bool,
     pub tenhou: bool,
     pub chihou: bool,
+
+    // ローカル役用（ruleset.local_yaku で有効なときのみ）
+    #[serde(default)]
+    pub renhou: bool,
+    #[serde(default)]
+    pub open_riichi: bool,
+    /// 立直宣言牌でのロン
+    #[serde(default)]
+    pub tsubame_gaeshi: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -71,30 +94,104 @@ pub struct ScoreRequest {
     pub kan_dora_indicators: Vec<String>,
     pub ura_indicators: Vec<String>,
     pub kan_ura_indicators: Vec<String>,
+    /// 抜きドラ（北抜き）の枚数。三麻、または rules.yonma_nukidora のときのみ
+    #[serde(default)]
+    pub nukidora: u32,
 
+    /// events があるときは省略でき、記録から決め直される
+    #[serde(default)]
     pub flags: Flags,
+    /// 局の記録（START から和了直前まで）。あれば状況役フラグをここから決める
+    #[serde(default)]
+    pub events: Option<Vec<RoundEvent>>,
+    /// ロン和了者の河と見逃した牌。あれば（なければ events から）フリテンを確認する
+    #[serde(default)]
+    pub furiten: Option<FuritenInfo>,
+
+    /// RuleSet・プリセット名（"ema"）・{"preset": "ema", ...上書き} のいずれか
+    #[serde(default, deserialize_with = "preset::deserialize_rules")]
+    pub rules: RuleSet,
 }
 
 #[derive(Debug, Serialize)]
 pub struct ScoreResult {
     pub total_points: u32,
     pub yakuman: u32,
+    /// 役満の倍率・複合の適用内容（役満のときのみ）
+    pub yakuman_policy: Option<String>,
     pub han: u32,
     pub fu: u32,
+    /// 符の内訳（ルールによる選択を含む）
+    pub fu_breakdown: Vec<String>,
     pub yaku: Vec<String>,
     pub dora_han: u32,
     pub ura_dora_han: u32,
     pub aka_dora_han: u32,
+    pub nukidora_han: u32,
+    /// フリテンのロンをチョンボとして扱った（点数は 0、理由は yaku）
+    /// - score は罰符を精算しない。呼び出し側が GameLedger::record_chombo に渡すこと
+    pub chombo: bool,
 }
 
+mod ankan;
+mod bot;
+mod calls;
+mod condition;
 mod decompose;
 mod dora;
 mod engine;
+mod flags;
 mod fu;
+mod furiten;
+mod game;
+mod local_yaku;
+mod mjai;
+mod mjlog;
 mod points;
+mod preset;
+mod round;
+mod rule_file;
+mod rules;
 mod score;
+mod settlement;
+mod shanten;
 mod special;
 mod tile;
+mod wait;
 mod yaku;
 
-pub use score::score;
+pub use ankan::{check_riichi_ankan, AnkanVerdict};
+pub use bot::MjaiBot;
+pub use calls::{discard_calls, draw_calls, CallOption};
+pub use condition::{
+    solve_conditions, ConditionReport, ConditionRequest, DrawCondition, RankRequirement,
+    WinCondition,
+};
+pub use flags::derive_flags;
+pub use furiten::{Furiten, FuritenInfo};
+pub use game::{GameEndReason, GameLedger, HandOutcome, HandRecord};
+pub use mjai::{
+    import_mjai, mjai_tile, parse_mjai, round_to_mjai, to_mjai_tile, verify_mjai, write_mjai,
+    MjaiEvent,
+};
+pub use mjlog::{
+    import_mjlog, verify_mjlog, verify_mjlog_file, Discrepancy, MjlogReport, MjlogRound, MjlogWin,
+    WinSummary,
+};
+pub use points::{
+    calc_points, han_fu_candidates, lookup_han_fu, score_table, HanFu, LimitTier, Payment,
+    PointBreakdown, ScoreTableRow,
+};
+pub use preset::{rules_from_value, rules_over, RulePreset};
+pub use round::{Action, Phase, PlayerState, Round, RoundEnd, RoundEvent};
+pub use rule_file::{load_rules_file, parse_rules, write_rules, RuleFormat};
+pub use rules::{
+    ChomboPayment, FuritenRon, GameLength, KazoeRule, KuikaeRule, LeftoverKyotaku, LocalYakuRules,
+    OpenPinfuFu, RenhouRule, RiichiAnkanRule, RuleSet, SanmaTsumo, ScoreRounding, Shibari,
+    YakumanRules, RULES_VERSION,
+};
+pub use score::{score, validate};
+pub use settlement::{settle_final, FinalStanding};
+pub use shanten::shanten;
+pub use tile::{Honor, Suit, Tile, TileKey};
+pub use wait::{is_complete, is_tenpai, winning_tiles};
@@ -0,0 +1,179 @@
+use crate::decompose::{Block, HandPattern};
+use crate::rules::RenhouRule;
+use crate::tile::{Suit, Tile, TileKey};
+use crate::yaku::YakuResult;
+use crate::{Riichi, ScoreRequest, WinType};
+use std::collections::HashMap;
+
+/// ローカル役（ruleset.local_yaku で個別に有効化）
+/// - pattern: 標準形の分解（七対子・国士など特殊形では None）
+pub fn eval_local_yaku(
+    pattern: Option<&HandPattern>,
+    tiles14: &[Tile],
+    win_tile: Tile,
+    req: &ScoreRequest,
+) -> YakuResult {
+    let lr = &req.rules.local_yaku;
+    let mut yakuman = 0;
+    let mut han = 0;
+    let mut min_han = 0;
+    let mut yaku = vec![];
+    let mut yakuman_yaku = vec![];
+
+    // ===== 人和（子の第一ツモ前のロン） =====
+    if req.flags.renhou && req.win_type == WinType::RON && !req.dealer {
+        match lr.renhou {
+            RenhouRule::OFF => {}
+            RenhouRule::MANGAN => {
+                yaku.push("人和".into());
+                min_han = min_han.max(5);
+            }
+            RenhouRule::YAKUMAN => {
+                yaku.push("人和".into());
+                yakuman_yaku.push("人和".into());
+                yakuman += 1;
+            }
+        }
+    }
+
+    // ===== 大車輪 / 大数隣 / 大竹林（2〜8 の七対子形） =====
+    if req.melds.is_empty() {
+        if let Some(suit) = daisharin_suit(tiles14) {
+            let (on, name) = match suit {
+                Suit::Pin => (lr.daisharin, "大車輪"),
+                Suit::Man => (lr.daisuurin, "大数隣"),
+                Suit::Sou => (lr.daichikurin, "大竹林"),
+                Suit::Honor => (false, ""),
+            };
+            if on {
+                yaku.push(name.into());
+                yakuman_yaku.push(name.into());
+                yakuman += 1;
+            }
+        }
+    }
+
+    // ===== 一筒摸月 / 九筒撈魚（満貫） =====
+    let wk = TileKey::from_tile(&win_tile);
+    if lr.iipin_moyue
+        && req.flags.haitei
+        && req.win_type == WinType::TSUMO
+        && wk.suit == Suit::Pin
+        && wk.num == 1
+    {
+        yaku.push("一筒摸月".into());
+        min_han = min_han.max(5);
+    }
+    if lr.chuupin_raoyui
+        && req.flags.houtei
+        && req.win_type == WinType::RON
+        && wk.suit == Suit::Pin
+        && wk.num == 9
+    {
+        yaku.push("九筒撈魚".into());
+        min_han = min_han.max(5);
+    }
+
+    // ===== オープン立直（立直に +1翻） =====
+    if lr.open_riichi && req.flags.open_riichi && req.flags.riichi != Riichi::NONE {
+        yaku.push("オープン立直".into());
+        han += 1;
+    }
+
+    // ===== 燕返し（立直宣言牌でロン） =====
+    if lr.tsubame_gaeshi && req.flags.tsubame_gaeshi && req.win_type == WinType::RON {
+        yaku.push("燕返し".into());
+        han += 1;
+    }
+
+    // ===== 面子構成の役 =====
+    if let Some(p) = pattern {
+        if lr.sanrenkou && is_sanrenkou(p) {
+            yaku.push("三連刻".into());
+            han += 2;
+        }
+        if lr.iishoku_sandoujun && is_iishoku_sandoujun(p) {
+            yaku.push("一色三同順".into());
+            han += if p.menzen { 3 } else { 2 };
+        }
+    }
+
+    YakuResult {
+        yakuman,
+        han,
+        min_han,
+        yaku,
+        yakuman_yaku,
+    }
+}
+
+/// 通常役にローカル役を合算する
+pub fn merge_local_yaku(base: &mut YakuResult, local: YakuResult) {
+    // 一色三同順は一盃口と複合しない
+    if local.yaku.iter().any(|y| y == "一色三同順") {
+        if let Some(i) = base.yaku.iter().position(|y| y == "一盃口") {
+            base.yaku.remove(i);
+            base.han -= 1;
+        }
+    }
+
+    base.yakuman += local.yakuman;
+    base.han += local.han;
+    base.min_han = base.min_han.max(local.min_han);
+    base.yaku.extend(local.yaku);
+    base.yakuman_yaku.extend(local.yakuman_yaku);
+
+    // 役満が成立したら通常役は無視
+    if base.yakuman > 0 {
+        base.han = 0;
+        base.min_han = 0;
+        base.yaku = base.yakuman_yaku.clone();
+    }
+}
+
+fn daisharin_suit(tiles14: &[Tile]) -> Option<Suit> {
+    let suit = tiles14.first()?.suit;
+    if suit == Suit::Honor || tiles14.len() != 14 {
+        return None;
+    }
+    let mut c = [0u8; 10];
+    for t in tiles14 {
+        if t.suit != suit {
+            return None;
+        }
+        c[t.num as usize] += 1;
+    }
+    if c[1] == 0 && c[9] == 0 && c[2..=8].iter().all(|&n| n == 2) {
+        Some(suit)
+    } else {
+        None
+    }
+}
+
+fn is_sanrenkou(pattern: &HandPattern) -> bool {
+    let mut keys: Vec<TileKey> = pattern
+        .blocks
+        .iter()
+        .filter_map(|b| match b {
+            Block::Koutsu(t, _, _) | Block::Kantsu(t, _, _, _) => Some(TileKey::from_tile(t)),
+            _ => None,
+        })
+        .filter(|k| k.suit != Suit::Honor)
+        .collect();
+    keys.sort_by_key(|k| (k.suit, k.num));
+
+    keys.iter().any(|k| {
+        let has = |n: u8| keys.iter().any(|x| x.suit == k.suit && x.num == n);
+        has(k.num + 1) && has(k.num + 2)
+    })
+}
+
+fn is_iishoku_sandoujun(pattern: &HandPattern) -> bool {
+    let mut m = HashMap::<(Suit, u8), u8>::new();
+    for b in &pattern.blocks {
+        if let Block::Shuntsu(a, _, _) = b {
+            *m.entry((a.suit, a.num)).or_insert(0) += 1;
+        }
+    }
+    m.values().any(|&c| c >= 3)
+}
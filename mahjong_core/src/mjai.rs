@@ -0,0 +1,739 @@
+use crate::calls::{meld, meld_tiles};
+use crate::mjlog::{compare_win, round_label, wind, MjlogReport, MjlogRound, MjlogWin, Replay};
+use crate::points::{calc_points, draw_deltas, payment_deltas};
+use crate::round::{Phase, Round, RoundEnd, RoundEvent};
+use crate::rules::RuleSet;
+use crate::tile::{Suit, Tile};
+use crate::{MeldType, Riichi, ScoreRequest, ScoreResult, WinSummary};
+use serde::{Deserialize, Serialize};
+
+/// mjai の役名、このエンジンでの役名、門前の翻数、副露の翻数（0 は門前のみ）
+/// - 三元牌は mjai では sangenpai 1つにまとめ、翻数を合算する
+/// - ダブル役満の形（四暗刻単騎など）は元の役名で、倍率は翻数に入れる
+const MJAI_YAKU: [(&str, &str, u32, u32); 46] = [
+    ("menzenchin_tsumoho", "門前清自摸和", 1, 0),
+    ("reach", "立直", 1, 0),
+    ("ippatsu", "一発", 1, 0),
+    ("chankan", "搶槓", 1, 1),
+    ("rinshankaiho", "嶺上開花", 1, 1),
+    ("haiteiraoyue", "海底摸月", 1, 1),
+    ("hoteiraoyui", "河底撈魚", 1, 1),
+    ("pinfu", "平和", 1, 0),
+    ("tanyaochu", "断么九", 1, 1),
+    ("ipeko", "一盃口", 1, 0),
+    ("jikaze", "役牌 自風", 1, 1),
+    ("bakaze", "役牌 場風", 1, 1),
+    ("sangenpai", "役牌 白", 1, 1),
+    ("sangenpai", "役牌 發", 1, 1),
+    ("sangenpai", "役牌 中", 1, 1),
+    ("double_reach", "ダブル立直", 2, 0),
+    ("chitoitsu", "七対子", 2, 0),
+    ("honchantaiyao", "混全帯么九", 2, 1),
+    ("ikkitsukan", "一気通貫", 2, 1),
+    ("sanshokudojun", "三色同順", 2, 1),
+    ("sanshokudoko", "三色同刻", 2, 2),
+    ("sankantsu", "三槓子", 2, 2),
+    ("toitoiho", "対々和", 2, 2),
+    ("sananko", "三暗刻", 2, 2),
+    ("shosangen", "小三元", 2, 2),
+    ("honroto", "混老頭", 2, 2),
+    ("ryanpeko", "二盃口", 3, 0),
+    ("junchantaiyao", "純全帯么九", 3, 2),
+    ("honiso", "混一色", 3, 2),
+    ("chiniso", "清一色", 6, 5),
+    ("renho", "人和", 13, 0),
+    ("tenho", "天和", 13, 0),
+    ("chiho", "地和", 13, 0),
+    ("daisangen", "大三元", 13, 13),
+    ("suanko", "四暗刻", 13, 0),
+    ("suanko", "四暗刻単騎", 13, 0),
+    ("tsuiso", "字一色", 13, 13),
+    ("ryuiso", "緑一色", 13, 13),
+    ("chinroto", "清老頭", 13, 13),
+    ("churenpoton", "九蓮宝燈", 13, 0),
+    ("churenpoton", "純正九蓮宝燈", 13, 0),
+    ("kokushimuso", "国士無双", 13, 0),
+    ("kokushimuso", "国士無双十三面待ち", 13, 0),
+    ("daisushi", "大四喜", 13, 13),
+    ("shosushi", "小四喜", 13, 13),
+    ("sukantsu", "四槓子", 13, 13),
+];
+
+const WINDS: [&str; 4] = ["E", "S", "W", "N"];
+
+/// mjai のイベント（1行1イベントの JSON）
+/// - 牌は mjai の表記（"5mr" は赤五、"?" は伏せた牌）
+/// - 席は起家からの番号
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MjaiEvent {
+    Hello {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        protocol: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        protocol_version: Option<u32>,
+    },
+    Join {
+        name: String,
+        #[serde(default)]
+        room: String,
+    },
+    StartGame {
+        /// 自分の席（クライアントへの通知のとき）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<usize>,
+        #[serde(default)]
+        names: Vec<String>,
+    },
+    StartKyoku {
+        bakaze: String,
+        /// 1〜4
+        kyoku: u32,
+        honba: u32,
+        kyotaku: u32,
+        oya: usize,
+        dora_marker: String,
+        tehais: Vec<Vec<String>>,
+    },
+    Tsumo {
+        actor: usize,
+        pai: String,
+    },
+    Dahai {
+        actor: usize,
+        pai: String,
+        tsumogiri: bool,
+    },
+    Chi {
+        actor: usize,
+        target: usize,
+        pai: String,
+        consumed: Vec<String>,
+    },
+    Pon {
+        actor: usize,
+        target: usize,
+        pai: String,
+        consumed: Vec<String>,
+    },
+    Daiminkan {
+        actor: usize,
+        target: usize,
+        pai: String,
+        consumed: Vec<String>,
+    },
+    Ankan {
+        actor: usize,
+        consumed: Vec<String>,
+    },
+    Kakan {
+        actor: usize,
+        pai: String,
+        /// ポンしていた3枚
+        consumed: Vec<String>,
+    },
+    Dora {
+        dora_marker: String,
+    },
+    Reach {
+        actor: usize,
+    },
+    ReachAccepted {
+        actor: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        deltas: Option<Vec<i32>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        scores: Option<Vec<i32>>,
+    },
+    Hora {
+        actor: usize,
+        /// 放銃者（ツモなら actor）
+        target: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pai: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        uradora_markers: Vec<String>,
+        /// 和了牌を除く手牌
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        hora_tehais: Vec<String>,
+        /// (役名, 翻数)。役満は1倍につき13翻
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        yakus: Vec<(String, u32)>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fu: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fan: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        hora_points: Option<u32>,
+        /// 席ごとの点数移動（本場・供託込み）
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        deltas: Vec<i32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        scores: Option<Vec<i32>>,
+    },
+    Ryukyoku {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tenpais: Vec<bool>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        deltas: Vec<i32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        scores: Option<Vec<i32>>,
+    },
+    EndKyoku,
+    EndGame,
+    Error {
+        #[serde(default)]
+        message: String,
+    },
+    /// 応答なし（鳴かない・和了しない）。知らない type のイベントもこれとして読む
+    #[serde(other)]
+    None,
+}
+
+/// mjai の牌表記からこのエンジンの牌へ（"5mr" → 赤五、字牌は同じ表記）
+pub fn mjai_tile(pai: &str) -> Result<Tile, String> {
+    match pai {
+        "?" => Err("伏せた牌（?）は変換できません".into()),
+        "5mr" => Tile::from_code("0m"),
+        "5pr" => Tile::from_code("0p"),
+        "5sr" => Tile::from_code("0s"),
+        _ => Tile::from_code(pai).map_err(|_| format!("mjai の牌表記が不正です: {}", pai)),
+    }
+}
+
+/// このエンジンの牌から mjai の牌表記へ
+pub fn to_mjai_tile(tile: &Tile) -> String {
+    if tile.red {
+        let suit = match tile.suit {
+            Suit::Man => 'm',
+            Suit::Pin => 'p',
+            _ => 's',
+        };
+        return format!("5{}r", suit);
+    }
+    tile.to_code()
+}
+
+/// mjai の1行1イベントを読む（空行は無視）
+pub fn parse_mjai(text: &str) -> Result<Vec<MjaiEvent>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| format!("{}行目: mjai イベントが不正です: {}", i + 1, e))
+        })
+        .collect()
+}
+
+/// mjai の1行1イベントで書く
+pub fn write_mjai(events: &[MjaiEvent]) -> String {
+    events
+        .iter()
+        .map(|e| serde_json::to_string(e).unwrap() + "\n")
+        .collect()
+}
+
+/// 局の進行を mjai のイベント列にする（start_kyoku から。終局していれば hora / ryukyoku と end_kyoku まで）
+/// - 立直は reach → dahai → reach_accepted（宣言牌でロンされたら reach_accepted なし）
+pub fn round_to_mjai(round: &Round) -> Vec<MjaiEvent> {
+    let n = round.players.len();
+    let tiles = |ts: &[Tile]| ts.iter().map(to_mjai_tile).collect::<Vec<_>>();
+    // 立直宣言牌が通ったか（最後の打牌なら流局のときのみ）
+    let passed = |i: usize| {
+        i + 1 < round.events.len() || matches!(round.phase, Phase::ENDED(RoundEnd::DRAW { .. }))
+    };
+    let accepted = round
+        .events
+        .iter()
+        .enumerate()
+        .filter(|(i, e)| matches!(e, RoundEvent::DISCARD { riichi: true, .. }) && passed(*i))
+        .count() as u32;
+
+    let dora = round.dora_indicators();
+    let mut out = vec![MjaiEvent::StartKyoku {
+        bakaze: WINDS[round.round_wind as usize].to_string(),
+        kyoku: round.dealer as u32 + 1,
+        honba: round.honba,
+        kyotaku: round.kyotaku - accepted,
+        oya: round.dealer,
+        dora_marker: to_mjai_tile(&dora[0]),
+        tehais: round.haipai.iter().map(|h| tiles(h)).collect(),
+    }];
+
+    let mut last_draw: Option<(usize, Tile)> = None;
+    let mut last_discard: Option<Tile> = None;
+    let mut pons: Vec<(usize, Vec<Tile>)> = vec![];
+    let mut first_dora = true;
+    for (i, e) in round.events.iter().enumerate() {
+        match e {
+            RoundEvent::START { .. } => {}
+            RoundEvent::DORA { indicator } => {
+                if !std::mem::take(&mut first_dora) {
+                    out.push(MjaiEvent::Dora {
+                        dora_marker: to_mjai_tile(indicator),
+                    });
+                }
+            }
+            RoundEvent::DRAW { seat, tile, .. } => {
+                last_draw = Some((*seat, *tile));
+                out.push(MjaiEvent::Tsumo {
+                    actor: *seat,
+                    pai: to_mjai_tile(tile),
+                });
+            }
+            RoundEvent::DISCARD { seat, tile, riichi } => {
+                if *riichi {
+                    out.push(MjaiEvent::Reach { actor: *seat });
+                }
+                out.push(MjaiEvent::Dahai {
+                    actor: *seat,
+                    pai: to_mjai_tile(tile),
+                    tsumogiri: last_draw == Some((*seat, *tile))
+                        && matches!(round.events.get(i - 1), Some(RoundEvent::DRAW { .. })),
+                });
+                last_discard = Some(*tile);
+                if *riichi && passed(i) {
+                    out.push(MjaiEvent::ReachAccepted {
+                        actor: *seat,
+                        deltas: None,
+                        scores: None,
+                    });
+                }
+            }
+            RoundEvent::CALL { seat, from, meld } => {
+                let called = last_discard.unwrap();
+                let mut consumed = meld_tiles(meld);
+                let pos = consumed.iter().position(|t| *t == called).unwrap();
+                consumed.remove(pos);
+                let (actor, target, pai, consumed) =
+                    (*seat, *from, to_mjai_tile(&called), tiles(&consumed));
+                out.push(match meld.meld_type {
+                    MeldType::CHI => MjaiEvent::Chi {
+                        actor,
+                        target,
+                        pai,
+                        consumed,
+                    },
+                    MeldType::PON => {
+                        pons.push((*seat, meld_tiles(meld)));
+                        MjaiEvent::Pon {
+                            actor,
+                            target,
+                            pai,
+                            consumed,
+                        }
+                    }
+                    _ => MjaiEvent::Daiminkan {
+                        actor,
+                        target,
+                        pai,
+                        consumed,
+                    },
+                });
+            }
+            RoundEvent::KAN { seat, meld } => {
+                let ts = meld_tiles(meld);
+                if meld.meld_type == MeldType::ANKAN {
+                    out.push(MjaiEvent::Ankan {
+                        actor: *seat,
+                        consumed: tiles(&ts),
+                    });
+                } else {
+                    let added = *ts.last().unwrap();
+                    last_discard = Some(added);
+                    out.push(MjaiEvent::Kakan {
+                        actor: *seat,
+                        pai: to_mjai_tile(&added),
+                        consumed: tiles(&ts[..ts.len() - 1]),
+                    });
+                }
+            }
+        }
+    }
+
+    match &round.phase {
+        Phase::ENDED(RoundEnd::WIN {
+            winner,
+            loser,
+            request,
+            result,
+        }) => {
+            let bd = calc_points(
+                result.fu,
+                result.han,
+                result.yakuman,
+                request.win_type,
+                request.dealer,
+                request.honba,
+                request.kyotaku,
+                &request.rules,
+            );
+            let riichi = request.flags.riichi != Riichi::NONE;
+            let ura: Vec<String> = request
+                .ura_indicators
+                .iter()
+                .chain(&request.kan_ura_indicators)
+                .filter_map(|c| Tile::from_code(c).ok())
+                .map(|t| to_mjai_tile(&t))
+                .collect();
+            let hand: Vec<Tile> = request
+                .hand_tiles
+                .iter()
+                .filter_map(|c| Tile::from_code(c).ok())
+                .collect();
+            out.push(MjaiEvent::Hora {
+                actor: *winner,
+                target: loser.unwrap_or(*winner),
+                pai: Tile::from_code(&request.win_tile)
+                    .ok()
+                    .map(|t| to_mjai_tile(&t)),
+                uradora_markers: if riichi { ura } else { vec![] },
+                hora_tehais: tiles(&hand),
+                yakus: mjai_yakus(request, result),
+                fu: Some(result.fu),
+                fan: Some(if result.yakuman > 0 {
+                    result.yakuman * 13
+                } else {
+                    result.han
+                }),
+                hora_points: Some(result.total_points),
+                deltas: payment_deltas(&bd, n, round.dealer, *winner, *loser),
+                scores: None,
+            });
+            out.push(MjaiEvent::EndKyoku);
+        }
+        Phase::ENDED(RoundEnd::DRAW { tenpai }) => {
+            out.push(MjaiEvent::Ryukyoku {
+                reason: Some("fanpai".into()),
+                tenpais: tenpai.clone(),
+                deltas: draw_deltas(tenpai),
+                scores: None,
+            });
+            out.push(MjaiEvent::EndKyoku);
+        }
+        _ => {}
+    }
+    out
+}
+
+/// 計算結果の役を mjai の (役名, 翻数) にする（表にない役は残りの翻数で日本語名のまま）
+fn mjai_yakus(req: &ScoreRequest, result: &ScoreResult) -> Vec<(String, u32)> {
+    let menzen = req.melds.iter().all(|m| m.meld_type == MeldType::ANKAN);
+    let names: Vec<&String> = result.yaku.iter().filter(|y| !y.contains("ドラ")).collect();
+
+    let mut yakus = vec![];
+    let mut unknown = vec![];
+    let mut rest = if result.yakuman > 0 {
+        result.yakuman * 13
+    } else {
+        result.han
+            - result.dora_han
+            - result.ura_dora_han
+            - result.aka_dora_han
+            - result.nukidora_han
+    };
+    for name in names {
+        match MJAI_YAKU.iter().find(|y| y.1 == name) {
+            Some(&(mjai, _, closed, open)) => {
+                let han = if menzen { closed } else { open };
+                rest = rest.saturating_sub(han);
+                match yakus.iter_mut().find(|(n, _)| n == mjai) {
+                    Some(y) => y.1 += han,
+                    None => yakus.push((mjai.to_string(), han)),
+                }
+            }
+            None => unknown.push(name.clone()),
+        }
+    }
+    // 役満の倍率（ダブル役満など）や表にない役の翻数
+    if let Some(name) = unknown.first() {
+        yakus.push((name.clone(), rest));
+    } else if rest > 0 {
+        if let Some(last) = yakus.last_mut() {
+            last.1 += rest;
+        }
+    }
+    if result.yakuman == 0 {
+        for (name, han) in [
+            ("dora", result.dora_han),
+            ("akadora", result.aka_dora_han),
+            ("uradora", result.ura_dora_han),
+        ] {
+            if han > 0 {
+                yakus.push((name.to_string(), han));
+            }
+        }
+    }
+    yakus
+}
+
+/// mjai の記録（サーバ側の全員の手牌が見えるもの）から全局を再現する
+/// - 卓ルールは記録にないので rules を使う
+pub fn import_mjai(text: &str, rules: &RuleSet) -> Result<Vec<MjlogRound>, String> {
+    let events = parse_mjai(text)?;
+    let mut rounds: Vec<MjlogRound> = vec![];
+    let mut replay: Option<Replay> = None;
+    let mut dora: Vec<Tile> = vec![];
+    // この局で成立した立直を含む供託
+    let mut kyotaku_now = 0;
+
+    for e in events {
+        let label = rounds.last().map(|r| r.label.clone()).unwrap_or_default();
+        let at = |e: String| format!("{}: {}", label, e);
+        let tile = |pai: &str| mjai_tile(pai).map_err(at);
+        let tiles = |ps: &[String]| ps.iter().map(|p| tile(p)).collect::<Result<Vec<_>, _>>();
+
+        if let MjaiEvent::StartKyoku {
+            bakaze,
+            kyoku,
+            honba,
+            kyotaku,
+            oya,
+            dora_marker,
+            tehais,
+        } = &e
+        {
+            let round_wind = WINDS
+                .iter()
+                .position(|w| w == bakaze)
+                .map(wind)
+                .ok_or_else(|| format!("場風が不正です: {}", bakaze))?;
+            let hands = tehais
+                .iter()
+                .map(|h| tiles(h))
+                .collect::<Result<Vec<_>, _>>()?;
+            if hands.len() != rules.players() {
+                return Err("配牌の人数がルールと一致しません".into());
+            }
+            if let (Some(r), Some(round)) = (replay.take(), rounds.last_mut()) {
+                round.events = r.events;
+            }
+            let index = round_wind as usize * 4 + (*kyoku as usize).saturating_sub(1);
+            rounds.push(MjlogRound {
+                label: round_label(index, *honba),
+                round_wind,
+                dealer: *oya,
+                honba: *honba,
+                kyotaku: *kyotaku,
+                events: vec![],
+                wins: vec![],
+            });
+            let mut r = Replay::new(*oya, hands);
+            r.seat(*oya)?;
+            dora = vec![tile(dora_marker)?];
+            kyotaku_now = *kyotaku;
+            r.events.push(RoundEvent::DORA { indicator: dora[0] });
+            replay = Some(r);
+            continue;
+        }
+
+        let Some(r) = replay.as_mut() else {
+            continue;
+        };
+        let round = rounds.last_mut().unwrap();
+        match &e {
+            MjaiEvent::Tsumo { actor, pai } => {
+                let seat = r.seat(*actor).map_err(at)?;
+                r.draw(seat, tile(pai)?);
+            }
+            MjaiEvent::Dahai { actor, pai, .. } => {
+                let seat = r.seat(*actor).map_err(at)?;
+                r.discard(seat, tile(pai)?).map_err(at)?;
+            }
+            MjaiEvent::Reach { actor } => {
+                let seat = r.seat(*actor).map_err(at)?;
+                r.reach[seat] = true;
+            }
+            MjaiEvent::ReachAccepted { .. } => kyotaku_now += 1,
+            MjaiEvent::Chi {
+                actor,
+                target,
+                pai,
+                consumed,
+            }
+            | MjaiEvent::Pon {
+                actor,
+                target,
+                pai,
+                consumed,
+            }
+            | MjaiEvent::Daiminkan {
+                actor,
+                target,
+                pai,
+                consumed,
+            } => {
+                let (seat, from) = (r.seat(*actor).map_err(at)?, r.seat(*target).map_err(at)?);
+                let called = tile(pai)?;
+                let mut all = tiles(consumed)?;
+                all.push(called);
+                let meld_type = match e {
+                    MjaiEvent::Chi { .. } => {
+                        all.sort_by_key(|t| t.num);
+                        MeldType::CHI
+                    }
+                    MjaiEvent::Pon { .. } => MeldType::PON,
+                    _ => MeldType::MINKAN,
+                };
+                r.call(seat, from, meld(meld_type, &all), called)
+                    .map_err(at)?;
+            }
+            MjaiEvent::Ankan { actor, consumed } => {
+                let seat = r.seat(*actor).map_err(at)?;
+                r.kan(seat, meld(MeldType::ANKAN, &tiles(consumed)?))
+                    .map_err(at)?;
+            }
+            MjaiEvent::Kakan {
+                actor,
+                pai,
+                consumed,
+            } => {
+                let seat = r.seat(*actor).map_err(at)?;
+                let mut all = tiles(consumed)?;
+                all.push(tile(pai)?);
+                r.kan(seat, meld(MeldType::MINKAN, &all)).map_err(at)?;
+            }
+            MjaiEvent::Dora { dora_marker } => {
+                let indicator = tile(dora_marker)?;
+                dora.push(indicator);
+                r.events.push(RoundEvent::DORA { indicator });
+            }
+            MjaiEvent::Hora { .. } => {
+                let win = mjai_win(r, &e, rules, round, kyotaku_now, &dora).map_err(at)?;
+                round.wins.push(win);
+            }
+            _ => {}
+        }
+    }
+    if let (Some(r), Some(round)) = (replay, rounds.last_mut()) {
+        round.events = r.events;
+    }
+    Ok(rounds)
+}
+
+/// mjai の記録の hora を1件ずつ score で再計算し、翻・符・役・点数移動を比べる
+pub fn verify_mjai(text: &str, rules: &RuleSet) -> Result<MjlogReport, String> {
+    let rounds = import_mjai(text, rules)?;
+    let mut report = MjlogReport {
+        rounds: rounds.len(),
+        ..Default::default()
+    };
+    for round in &rounds {
+        for win in &round.wins {
+            report.wins += 1;
+            match compare_win(&round.label, win, mjai_yaku_name) {
+                Some(d) => report.discrepancies.push(d),
+                None => report.matched += 1,
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// このエンジンの役名から mjai の役名へ（表にない役はそのまま）
+fn mjai_yaku_name(name: &str) -> &str {
+    MJAI_YAKU
+        .iter()
+        .find(|y| y.1 == name)
+        .map(|y| y.0)
+        .unwrap_or(name)
+}
+
+fn mjai_win(
+    r: &Replay,
+    hora: &MjaiEvent,
+    rules: &RuleSet,
+    round: &MjlogRound,
+    kyotaku: u32,
+    dora: &[Tile],
+) -> Result<MjlogWin, String> {
+    let MjaiEvent::Hora {
+        actor,
+        target,
+        pai,
+        uradora_markers,
+        yakus,
+        fu,
+        fan,
+        deltas,
+        ..
+    } = hora
+    else {
+        unreachable!()
+    };
+    let winner = r.seat(*actor)?;
+    let from = r.seat(*target)?;
+    let loser = (from != winner).then_some(from);
+    let win_tile = match (pai, loser, r.events.last()) {
+        (Some(p), _, _) => mjai_tile(p)?,
+        (None, None, Some(RoundEvent::DRAW { tile, .. })) => *tile,
+        (None, Some(_), _) => r
+            .events
+            .iter()
+            .rev()
+            .find_map(|e| match e {
+                RoundEvent::DISCARD { tile, .. } => Some(*tile),
+                RoundEvent::KAN { meld, .. } if meld.meld_type == MeldType::MINKAN => {
+                    meld_tiles(meld).last().copied()
+                }
+                _ => None,
+            })
+            .ok_or("ロン和了の牌がありません")?,
+        _ => return Err("ツモ和了の牌がありません".into()),
+    };
+    let ura = uradora_markers
+        .iter()
+        .map(|p| mjai_tile(p))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // 2人目以降のロンに供託は付かない
+    let kyotaku = if round.wins.is_empty() { kyotaku } else { 0 };
+    let request = r.request(
+        rules,
+        round.round_wind,
+        round.dealer,
+        round.honba,
+        kyotaku,
+        winner,
+        loser,
+        win_tile,
+        dora,
+        &ura,
+    )?;
+
+    let mut han = 0;
+    let mut yakuman = 0;
+    // 役名は mjai のまま残し、比べるときに計算結果の役名を mjai に直す
+    let mut yaku = vec![];
+    for (name, h) in yakus {
+        if *h >= 13 {
+            yakuman += h / 13;
+        } else {
+            han += h;
+        }
+        match name.as_str() {
+            "dora" | "akadora" | "uradora" => {}
+            // 三元牌は1つ1翻なので翻数の分だけ並べる
+            "sangenpai" => yaku.extend((0..*h).map(|_| name.clone())),
+            _ => yaku.push(name.clone()),
+        }
+    }
+    yaku.sort();
+
+    Ok(MjlogWin {
+        winner,
+        loser,
+        pao: None,
+        request,
+        log: WinSummary {
+            han: if yakuman > 0 { 0 } else { fan.unwrap_or(han) },
+            fu: *fu,
+            yakuman,
+            yaku,
+            deltas: deltas.clone(),
+        },
+    })
+}
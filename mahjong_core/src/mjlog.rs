@@ -0,0 +1,1207 @@
+use crate::calls::meld;
+use crate::points::{calc_points, payment_deltas};
+use crate::preset::RulePreset;
+use crate::round::RoundEvent;
+use crate::rules::RuleSet;
+use crate::score::score;
+use crate::tile::{Tile, TileKey};
+use crate::{Meld, MeldType, ScoreRequest, WinType, Wind};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// 天鳳の役（mjlog の役番号順）と、このエンジンでの役名
+const TENHOU_YAKU: [(&str, &str); 55] = [
+    ("門前清自摸和", "門前清自摸和"),
+    ("立直", "立直"),
+    ("一発", "一発"),
+    ("槍槓", "搶槓"),
+    ("嶺上開花", "嶺上開花"),
+    ("海底摸月", "海底摸月"),
+    ("河底撈魚", "河底撈魚"),
+    ("平和", "平和"),
+    ("断幺九", "断么九"),
+    ("一盃口", "一盃口"),
+    ("自風 東", "役牌 自風"),
+    ("自風 南", "役牌 自風"),
+    ("自風 西", "役牌 自風"),
+    ("自風 北", "役牌 自風"),
+    ("場風 東", "役牌 場風"),
+    ("場風 南", "役牌 場風"),
+    ("場風 西", "役牌 場風"),
+    ("場風 北", "役牌 場風"),
+    ("役牌 白", "役牌 白"),
+    ("役牌 發", "役牌 發"),
+    ("役牌 中", "役牌 中"),
+    ("両立直", "ダブル立直"),
+    ("七対子", "七対子"),
+    ("混全帯幺九", "混全帯么九"),
+    ("一気通貫", "一気通貫"),
+    ("三色同順", "三色同順"),
+    ("三色同刻", "三色同刻"),
+    ("三槓子", "三槓子"),
+    ("対々和", "対々和"),
+    ("三暗刻", "三暗刻"),
+    ("小三元", "小三元"),
+    ("混老頭", "混老頭"),
+    ("二盃口", "二盃口"),
+    ("純全帯幺九", "純全帯么九"),
+    ("混一色", "混一色"),
+    ("清一色", "清一色"),
+    ("人和", "人和"),
+    ("天和", "天和"),
+    ("地和", "地和"),
+    ("大三元", "大三元"),
+    ("四暗刻", "四暗刻"),
+    ("四暗刻単騎", "四暗刻単騎"),
+    ("字一色", "字一色"),
+    ("緑一色", "緑一色"),
+    ("清老頭", "清老頭"),
+    ("九蓮宝燈", "九蓮宝燈"),
+    ("純正九蓮宝燈", "純正九蓮宝燈"),
+    ("国士無双", "国士無双"),
+    ("国士無双１３面", "国士無双十三面待ち"),
+    ("大四喜", "大四喜"),
+    ("小四喜", "小四喜"),
+    ("四槓子", "四槓子"),
+    ("ドラ", "ドラ"),
+    ("裏ドラ", "裏ドラ"),
+    ("赤ドラ", "赤ドラ"),
+];
+
+/// 和了の要約（役はドラを除いて並べ替えたもの）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WinSummary {
+    pub han: u32,
+    /// 牌譜に符がない（満貫以上の JSON 形式など）ときは None
+    pub fu: Option<u32>,
+    pub yakuman: u32,
+    pub yaku: Vec<String>,
+    /// 席ごとの点数移動（本場・供託込み）
+    pub deltas: Vec<i32>,
+}
+
+/// 牌譜の和了1件
+#[derive(Debug, Clone, Serialize)]
+pub struct MjlogWin {
+    pub winner: usize,
+    /// None ならツモ
+    pub loser: Option<usize>,
+    /// 責任払いの相手（点数移動は比較しない）
+    pub pao: Option<usize>,
+    /// 局の記録（events）付きのリクエスト
+    pub request: ScoreRequest,
+    pub log: WinSummary,
+}
+
+/// 牌譜から再現した1局
+#[derive(Debug, Clone, Serialize)]
+pub struct MjlogRound {
+    /// 例: "東1局 0本場"
+    pub label: String,
+    pub round_wind: Wind,
+    pub dealer: usize,
+    pub honba: u32,
+    pub kyotaku: u32,
+    pub events: Vec<RoundEvent>,
+    pub wins: Vec<MjlogWin>,
+}
+
+/// 牌譜と計算結果の食い違い
+#[derive(Debug, Clone, Serialize)]
+pub struct Discrepancy {
+    pub round: String,
+    pub winner: usize,
+    pub loser: Option<usize>,
+    /// 一致しなかった項目（han / fu / yakuman / yaku / deltas / error）
+    pub fields: Vec<String>,
+    pub log: WinSummary,
+    pub ours: WinSummary,
+    /// 計算できなかったときの理由
+    pub error: Option<String>,
+    pub request: ScoreRequest,
+}
+
+/// 牌譜の再計算結果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MjlogReport {
+    pub rounds: usize,
+    pub wins: usize,
+    pub matched: usize,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+/// 天鳳の牌譜（mjlog XML / tenhou.net/6 の JSON）から全局を再現する
+/// - ルールは天鳳プリセットに、牌譜の卓ルール（赤・喰いタン・三麻）を反映する
+pub fn import_mjlog(text: &str) -> Result<Vec<MjlogRound>, String> {
+    match text.trim_start().chars().next() {
+        Some('<') => import_xml(text),
+        Some('{') => import_json(text),
+        _ => Err("天鳳の牌譜（XML / JSON）ではありません".into()),
+    }
+}
+
+/// 牌譜の和了を1件ずつ score で再計算し、翻・符・役・点数移動を比べる
+pub fn verify_mjlog(text: &str) -> Result<MjlogReport, String> {
+    let rounds = import_mjlog(text)?;
+    let mut report = MjlogReport {
+        rounds: rounds.len(),
+        ..Default::default()
+    };
+    for round in &rounds {
+        for win in &round.wins {
+            report.wins += 1;
+            match compare_win(&round.label, win, |name| name) {
+                Some(d) => report.discrepancies.push(d),
+                None => report.matched += 1,
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// ファイルから読み込んで verify_mjlog（gzip 圧縮のままは不可）
+pub fn verify_mjlog_file(path: &Path) -> Result<MjlogReport, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        return Err(format!(
+            "{}: gzip 圧縮された牌譜は展開してから読み込んでください",
+            path.display()
+        ));
+    }
+    let text = String::from_utf8(bytes).map_err(|e| format!("{}: {}", path.display(), e))?;
+    verify_mjlog(&text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// 牌譜の和了1件を score で再計算して比べる
+/// - rename は計算結果の役名を牌譜側の役名に直す
+pub(crate) fn compare_win(
+    label: &str,
+    win: &MjlogWin,
+    rename: fn(&str) -> &str,
+) -> Option<Discrepancy> {
+    let req = &win.request;
+    let result = score(req);
+    let log = &win.log;
+
+    let mut fields = vec![];
+    let mut error = None;
+    let mut ours = WinSummary {
+        deltas: vec![0; log.deltas.len()],
+        ..Default::default()
+    };
+    if result.total_points == 0 {
+        fields.push("error".to_string());
+        error = Some(result.yaku.join(" / "));
+    } else {
+        let win_type = req.win_type;
+        let bd = calc_points(
+            result.fu,
+            result.han,
+            result.yakuman,
+            win_type,
+            req.dealer,
+            req.honba,
+            req.kyotaku,
+            &req.rules,
+        );
+        let dealer = match &req.events.as_deref() {
+            Some([RoundEvent::START { dealer, .. }, ..]) => *dealer,
+            _ => 0,
+        };
+        ours = WinSummary {
+            han: result.han,
+            fu: Some(result.fu),
+            yakuman: result.yakuman,
+            yaku: summary_yaku(result.yaku.iter().map(|s| rename(s))),
+            deltas: payment_deltas(&bd, req.rules.players(), dealer, win.winner, win.loser),
+        };
+
+        if log.yakuman > 0 || ours.yakuman > 0 {
+            if log.yakuman != ours.yakuman {
+                fields.push("yakuman".into());
+            }
+        } else {
+            if log.han != ours.han {
+                fields.push("han".into());
+            }
+            if log.han <= 4 && log.fu.is_some() && log.fu != ours.fu {
+                fields.push("fu".into());
+            }
+        }
+        if log.yaku != ours.yaku {
+            fields.push("yaku".into());
+        }
+        if win.pao.is_none() && log.deltas != ours.deltas {
+            fields.push("deltas".into());
+        }
+    }
+
+    if fields.is_empty() {
+        return None;
+    }
+    Some(Discrepancy {
+        round: label.to_string(),
+        winner: win.winner,
+        loser: win.loser,
+        fields,
+        log: log.clone(),
+        ours,
+        error,
+        request: req.clone(),
+    })
+}
+
+/// 役名の一覧をドラ抜き・並べ替え済みにする
+fn summary_yaku<'a>(names: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut yaku: Vec<String> = names
+        .filter(|n| !n.contains("ドラ"))
+        .map(|n| n.to_string())
+        .collect();
+    yaku.sort();
+    yaku
+}
+
+fn our_yaku_name(tenhou: &str) -> &str {
+    TENHOU_YAKU
+        .iter()
+        .find(|(t, _)| *t == tenhou)
+        .map(|(_, ours)| *ours)
+        .unwrap_or(tenhou)
+}
+
+// ===== 局の再現 =====
+
+/// 牌譜を1手ずつ適用して手牌・副露・局の記録を作る
+pub(crate) struct Replay {
+    pub(crate) players: usize,
+    pub(crate) hands: Vec<Vec<Tile>>,
+    melds: Vec<Vec<Meld>>,
+    nukidora: Vec<u32>,
+    pub(crate) events: Vec<RoundEvent>,
+    /// 次の打牌が立直宣言牌
+    pub(crate) reach: Vec<bool>,
+    /// 次のツモが嶺上牌
+    rinshan: Vec<bool>,
+    /// 最後に捨てた（加槓した）牌
+    last_tile: Option<Tile>,
+}
+
+impl Replay {
+    pub(crate) fn new(dealer: usize, hands: Vec<Vec<Tile>>) -> Replay {
+        let players = hands.len();
+        let total = if players == 3 { 108 } else { 136 };
+        Replay {
+            players,
+            events: vec![RoundEvent::START {
+                dealer,
+                wall: total - 14 - 13 * players,
+            }],
+            hands,
+            melds: vec![vec![]; players],
+            nukidora: vec![0; players],
+            reach: vec![false; players],
+            rinshan: vec![false; players],
+            last_tile: None,
+        }
+    }
+
+    pub(crate) fn seat(&self, seat: usize) -> Result<usize, String> {
+        if seat < self.players {
+            Ok(seat)
+        } else {
+            Err(format!("席番号が不正です: {}", seat))
+        }
+    }
+
+    fn take(&mut self, seat: usize, tile: Tile) -> Result<(), String> {
+        let hand = &mut self.hands[seat];
+        match hand.iter().position(|t| *t == tile) {
+            Some(i) => {
+                hand.remove(i);
+                Ok(())
+            }
+            None => Err(format!("席{}の手牌に{}がありません", seat, tile.to_code())),
+        }
+    }
+
+    pub(crate) fn draw(&mut self, seat: usize, tile: Tile) {
+        let rinshan = std::mem::take(&mut self.rinshan[seat]);
+        self.hands[seat].push(tile);
+        self.events.push(RoundEvent::DRAW {
+            seat,
+            tile,
+            rinshan,
+        });
+    }
+
+    pub(crate) fn discard(&mut self, seat: usize, tile: Tile) -> Result<(), String> {
+        self.take(seat, tile)?;
+        let riichi = std::mem::take(&mut self.reach[seat]);
+        self.last_tile = Some(tile);
+        self.events.push(RoundEvent::DISCARD { seat, tile, riichi });
+        Ok(())
+    }
+
+    /// チー・ポン・大明槓（called: 鳴いた牌）
+    pub(crate) fn call(
+        &mut self,
+        seat: usize,
+        from: usize,
+        m: Meld,
+        called: Tile,
+    ) -> Result<(), String> {
+        let mut used = false;
+        for t in meld_tiles(&m)? {
+            if t == called && !used {
+                used = true;
+                continue;
+            }
+            self.take(seat, t)?;
+        }
+        if m.meld_type == MeldType::MINKAN {
+            self.rinshan[seat] = true;
+        }
+        self.melds[seat].push(m.clone());
+        self.events.push(RoundEvent::CALL {
+            seat,
+            from,
+            meld: m,
+        });
+        Ok(())
+    }
+
+    /// 暗槓（ANKAN）・加槓（MINKAN、最後の牌が加えた牌）
+    pub(crate) fn kan(&mut self, seat: usize, m: Meld) -> Result<(), String> {
+        let tiles = meld_tiles(&m)?;
+        if m.meld_type == MeldType::ANKAN {
+            for t in &tiles {
+                self.take(seat, *t)?;
+            }
+            self.melds[seat].push(m.clone());
+        } else {
+            let added = *tiles.last().unwrap();
+            self.take(seat, added)?;
+            let k = TileKey::from_tile(&added);
+            let pon = self.melds[seat]
+                .iter_mut()
+                .find(|p| {
+                    p.meld_type == MeldType::PON
+                        && Tile::from_code(&p.tiles[0]).is_ok_and(|t| TileKey::from_tile(&t) == k)
+                })
+                .ok_or_else(|| format!("席{}に加槓できるポンがありません", seat))?;
+            *pon = m.clone();
+            self.last_tile = Some(added);
+        }
+        self.rinshan[seat] = true;
+        self.events.push(RoundEvent::KAN { seat, meld: m });
+        Ok(())
+    }
+
+    /// 三麻の北抜き
+    fn nuki(&mut self, seat: usize, tile: Tile) -> Result<(), String> {
+        self.take(seat, tile)?;
+        self.nukidora[seat] += 1;
+        self.rinshan[seat] = true;
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn request(
+        &self,
+        rules: &RuleSet,
+        round_wind: Wind,
+        dealer: usize,
+        honba: u32,
+        kyotaku: u32,
+        winner: usize,
+        loser: Option<usize>,
+        win_tile: Tile,
+        dora: &[Tile],
+        ura: &[Tile],
+    ) -> Result<ScoreRequest, String> {
+        let mut hand = self.hands[winner].clone();
+        if loser.is_none() {
+            let i = hand
+                .iter()
+                .position(|t| *t == win_tile)
+                .ok_or_else(|| format!("席{}の手牌に和了牌がありません", winner))?;
+            hand.remove(i);
+        }
+        // 槓ドラの表示は和了に関係しない
+        let mut events = self.events.clone();
+        while matches!(events.last(), Some(RoundEvent::DORA { .. })) {
+            events.pop();
+        }
+
+        let codes = |tiles: &[Tile]| tiles.iter().map(|t| t.to_code()).collect::<Vec<_>>();
+        let split = |tiles: &[Tile]| match tiles.split_first() {
+            Some((first, rest)) => (vec![first.to_code()], codes(rest)),
+            None => (vec![], vec![]),
+        };
+        let (dora_indicators, kan_dora_indicators) = split(dora);
+        let (ura_indicators, kan_ura_indicators) = split(ura);
+        Ok(ScoreRequest {
+            round_wind,
+            seat_wind: wind((winner + self.players - dealer) % self.players),
+            kyotaku,
+            honba,
+            win_type: if loser.is_none() {
+                WinType::TSUMO
+            } else {
+                WinType::RON
+            },
+            dealer: winner == dealer,
+            hand_tiles: codes(&hand),
+            win_tile: win_tile.to_code(),
+            melds: self.melds[winner].clone(),
+            dora_indicators,
+            kan_dora_indicators,
+            ura_indicators,
+            kan_ura_indicators,
+            nukidora: self.nukidora[winner],
+            flags: Default::default(),
+            events: Some(events),
+            furiten: None,
+            rules: rules.clone(),
+        })
+    }
+}
+
+fn meld_tiles(m: &Meld) -> Result<Vec<Tile>, String> {
+    m.tiles.iter().map(|c| Tile::from_code(c)).collect()
+}
+
+pub(crate) fn wind(i: usize) -> Wind {
+    match i % 4 {
+        0 => Wind::E,
+        1 => Wind::S,
+        2 => Wind::W,
+        _ => Wind::N,
+    }
+}
+
+pub(crate) fn round_label(kyoku: usize, honba: u32) -> String {
+    let name = ["東", "南", "西", "北"][kyoku / 4 % 4];
+    format!("{}{}局 {}本場", name, kyoku % 4 + 1, honba)
+}
+
+/// 天鳳の卓ルール
+fn tenhou_rules(aka: bool, kuitan: bool, sanma: bool) -> RuleSet {
+    let mut rules = RulePreset::TENHOU.rules();
+    if !aka {
+        rules.aka_dora = 0;
+    }
+    rules.kuitan = kuitan;
+    rules.sanma = sanma;
+    rules
+}
+
+// ===== mjlog XML =====
+
+type Attrs = Vec<(String, String)>;
+
+/// mjlog の要素（<TAG a="1" .../>）を順に取り出す
+fn xml_tags(text: &str) -> Vec<(String, Attrs)> {
+    let mut tags = vec![];
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let body = rest[..end].trim_end_matches('/');
+        rest = &rest[end + 1..];
+        if body.starts_with(['?', '/', '!']) {
+            continue;
+        }
+
+        let name_end = body.find(char::is_whitespace).unwrap_or(body.len());
+        let name = body[..name_end].to_string();
+        let mut attrs = vec![];
+        let mut a = &body[name_end..];
+        while let Some(eq) = a.find("=\"") {
+            let key = a[..eq].trim().to_string();
+            let v = &a[eq + 2..];
+            let Some(close) = v.find('"') else {
+                break;
+            };
+            attrs.push((key, v[..close].to_string()));
+            a = &v[close + 1..];
+        }
+        tags.push((name, attrs));
+    }
+    tags
+}
+
+fn attr<'a>(attrs: &'a Attrs, key: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.as_str())
+}
+
+fn attr_nums(attrs: &Attrs, key: &str) -> Result<Vec<i64>, String> {
+    match attr(attrs, key) {
+        None | Some("") => Ok(vec![]),
+        Some(v) => v
+            .split(',')
+            .map(|n| {
+                n.trim()
+                    .parse()
+                    .map_err(|_| format!("{} の値が不正です: {}", key, v))
+            })
+            .collect(),
+    }
+}
+
+fn attr_num(attrs: &Attrs, key: &str) -> Result<i64, String> {
+    attr_nums(attrs, key)?
+        .first()
+        .copied()
+        .ok_or_else(|| format!("{} がありません", key))
+}
+
+/// 牌番号（0〜135）。赤ありなら 16・52・88 が赤五
+fn id_tile(id: i64, aka: bool) -> Result<Tile, String> {
+    if !(0..136).contains(&id) {
+        return Err(format!("牌番号が不正です: {}", id));
+    }
+    let mut t = TileKey::all()[id as usize / 4].to_tile();
+    t.red = aka && matches!(id, 16 | 52 | 88);
+    Ok(t)
+}
+
+/// 副露の種類
+enum XmlMeld {
+    /// チー・ポン・大明槓（from: 鳴いた相手、called: 鳴いた牌）
+    Call {
+        from: usize,
+        meld: Meld,
+        called: Tile,
+    },
+    /// 暗槓・加槓
+    Kan(Meld),
+    Nuki(Tile),
+}
+
+/// <N m="..."> の副露コード
+fn decode_meld(m: i64, who: usize, players: usize, aka: bool) -> Result<XmlMeld, String> {
+    let from = (who + (m & 3) as usize) % players;
+    let tile = |id: i64| id_tile(id, aka);
+    let tiles = |ids: &[i64]| {
+        ids.iter()
+            .map(|&id| tile(id))
+            .collect::<Result<Vec<_>, _>>()
+    };
+
+    if m & 0x4 != 0 {
+        let t = m >> 10;
+        let r = (t % 3) as usize;
+        let t = t / 3;
+        let base = (t / 7) * 9 + t % 7;
+        let ids: Vec<i64> = (0..3)
+            .map(|i| (base + i) * 4 + ((m >> (3 + 2 * i)) & 3))
+            .collect();
+        return Ok(XmlMeld::Call {
+            from,
+            meld: meld(MeldType::CHI, &tiles(&ids)?),
+            called: tile(ids[r])?,
+        });
+    }
+    if m & 0x18 != 0 {
+        let unused = (m >> 5) & 3;
+        let t = m >> 9;
+        let r = (t % 3) as usize;
+        let kind = t / 3;
+        let ids: Vec<i64> = (0..4)
+            .filter(|&i| i != unused)
+            .map(|i| kind * 4 + i)
+            .collect();
+        if m & 0x8 != 0 {
+            return Ok(XmlMeld::Call {
+                from,
+                meld: meld(MeldType::PON, &tiles(&ids)?),
+                called: tile(ids[r])?,
+            });
+        }
+        let mut all = tiles(&ids)?;
+        all.push(tile(kind * 4 + unused)?);
+        return Ok(XmlMeld::Kan(meld(MeldType::MINKAN, &all)));
+    }
+    if m & 0x20 != 0 {
+        return Ok(XmlMeld::Nuki(tile(m >> 8)?));
+    }
+
+    let called = m >> 8;
+    let kind = called / 4;
+    let ids: Vec<i64> = (0..4).map(|i| kind * 4 + i).collect();
+    if m & 3 == 0 {
+        Ok(XmlMeld::Kan(meld(MeldType::ANKAN, &tiles(&ids)?)))
+    } else {
+        Ok(XmlMeld::Call {
+            from,
+            meld: meld(MeldType::MINKAN, &tiles(&ids)?),
+            called: tile(called)?,
+        })
+    }
+}
+
+fn import_xml(text: &str) -> Result<Vec<MjlogRound>, String> {
+    let mut rules = tenhou_rules(true, true, false);
+    let mut aka = true;
+    let mut rounds: Vec<MjlogRound> = vec![];
+    let mut replay: Option<Replay> = None;
+
+    for (name, attrs) in xml_tags(text) {
+        let label = rounds.last().map(|r| r.label.clone()).unwrap_or_default();
+        let at = |e: String| format!("{}: {}", label, e);
+
+        match name.as_str() {
+            "GO" => {
+                let ty = attr_num(&attrs, "type")?;
+                aka = ty & 0x02 == 0;
+                rules = tenhou_rules(aka, ty & 0x04 == 0, ty & 0x10 != 0);
+            }
+            "INIT" => {
+                let seed = attr_nums(&attrs, "seed")?;
+                let [kyoku, honba, kyotaku, ..] = seed[..] else {
+                    return Err("INIT の seed が不正です".into());
+                };
+                let dealer = attr_num(&attrs, "oya")? as usize;
+                let mut hands = vec![];
+                for seat in 0..4 {
+                    let ids = attr_nums(&attrs, &format!("hai{}", seat))?;
+                    if ids.is_empty() {
+                        continue;
+                    }
+                    hands.push(
+                        ids.iter()
+                            .map(|&id| id_tile(id, aka))
+                            .collect::<Result<_, _>>()?,
+                    );
+                }
+                if hands.len() != rules.players() {
+                    return Err("配牌の人数がルールと一致しません".into());
+                }
+                let r = Replay::new(dealer, hands);
+                r.seat(dealer)
+                    .map_err(|e| format!("INIT の親が不正です: {}", e))?;
+                if let (Some(r), Some(round)) = (replay.take(), rounds.last_mut()) {
+                    round.events = r.events;
+                }
+                rounds.push(MjlogRound {
+                    label: round_label(kyoku as usize, honba as u32),
+                    round_wind: wind(kyoku as usize / 4),
+                    dealer,
+                    honba: honba as u32,
+                    kyotaku: kyotaku as u32,
+                    events: vec![],
+                    wins: vec![],
+                });
+                replay = Some(r);
+            }
+            "REACH" => {
+                let r = replay.as_mut().ok_or("INIT の前に REACH があります")?;
+                if attr_num(&attrs, "step")? == 1 {
+                    let seat = r.seat(attr_num(&attrs, "who")? as usize).map_err(at)?;
+                    r.reach[seat] = true;
+                }
+            }
+            "N" => {
+                let r = replay.as_mut().ok_or("INIT の前に N があります")?;
+                let who = r.seat(attr_num(&attrs, "who")? as usize).map_err(at)?;
+                let m = attr_num(&attrs, "m")?;
+                match decode_meld(m, who, r.players, aka).map_err(at)? {
+                    XmlMeld::Call { from, meld, called } => r.call(who, from, meld, called),
+                    XmlMeld::Kan(meld) => r.kan(who, meld),
+                    XmlMeld::Nuki(t) => r.nuki(who, t),
+                }
+                .map_err(at)?;
+            }
+            "DORA" => {
+                let r = replay.as_mut().ok_or("INIT の前に DORA があります")?;
+                let indicator = id_tile(attr_num(&attrs, "hai")?, aka)?;
+                r.events.push(RoundEvent::DORA { indicator });
+            }
+            "AGARI" => {
+                let r = replay.as_ref().ok_or("INIT の前に AGARI があります")?;
+                let round = rounds.last_mut().unwrap();
+                let win = xml_win(r, &attrs, &rules, round, aka).map_err(at)?;
+                round.wins.push(win);
+            }
+            _ => {
+                let mut chars = name.chars();
+                let (Some(c), digits) = (chars.next(), chars.as_str()) else {
+                    continue;
+                };
+                let Ok(id) = digits.parse::<i64>() else {
+                    continue;
+                };
+                let Some(r) = replay.as_mut() else {
+                    continue;
+                };
+                let tile = id_tile(id, aka).map_err(at)?;
+                match c {
+                    'T' | 'U' | 'V' | 'W' => {
+                        let seat = r.seat(c as usize - 'T' as usize).map_err(at)?;
+                        r.draw(seat, tile);
+                    }
+                    'D' | 'E' | 'F' | 'G' => {
+                        let seat = r.seat(c as usize - 'D' as usize).map_err(at)?;
+                        r.discard(seat, tile).map_err(at)?;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    if let (Some(r), Some(round)) = (replay, rounds.last_mut()) {
+        round.events = r.events;
+    }
+    Ok(rounds)
+}
+
+fn xml_win(
+    r: &Replay,
+    attrs: &Attrs,
+    rules: &RuleSet,
+    round: &MjlogRound,
+    aka: bool,
+) -> Result<MjlogWin, String> {
+    let winner = r.seat(attr_num(attrs, "who")? as usize)?;
+    let from = r.seat(attr_num(attrs, "fromWho")? as usize)?;
+    let loser = (from != winner).then_some(from);
+    let pao = attr(attrs, "paoWho")
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|&p| p != winner);
+    let ba = attr_nums(attrs, "ba")?;
+    let (honba, kyotaku) = (
+        ba.first().copied().unwrap_or(0),
+        ba.get(1).copied().unwrap_or(0),
+    );
+
+    let tiles = |key: &str| -> Result<Vec<Tile>, String> {
+        attr_nums(attrs, key)?
+            .into_iter()
+            .map(|id| id_tile(id, aka))
+            .collect()
+    };
+    let win_tile = id_tile(attr_num(attrs, "machi")?, aka)?;
+
+    // 手牌の再現を牌譜の手牌と照合する
+    let mut replayed = r.hands[winner].clone();
+    if loser.is_some() {
+        replayed.push(win_tile);
+    }
+    let mut expected = tiles("hai")?;
+    let sort = |v: &mut Vec<Tile>| v.sort_by_key(|t| (TileKey::from_tile(t).index(), t.red));
+    sort(&mut replayed);
+    sort(&mut expected);
+    if replayed != expected {
+        return Err(format!("席{}の手牌の再現が牌譜と一致しません", winner));
+    }
+
+    let request = r.request(
+        rules,
+        round.round_wind,
+        round.dealer,
+        honba as u32,
+        kyotaku as u32,
+        winner,
+        loser,
+        win_tile,
+        &tiles("doraHai")?,
+        &tiles("doraHaiUra")?,
+    )?;
+
+    let yaku = attr_nums(attrs, "yaku")?;
+    let yakuman = attr_nums(attrs, "yakuman")?;
+    let mut names = vec![];
+    let mut han = 0;
+    for pair in yaku.chunks(2) {
+        let (id, h) = (pair[0] as usize, pair.get(1).copied().unwrap_or(0) as u32);
+        let name = TENHOU_YAKU.get(id).map(|y| y.1).unwrap_or("不明な役");
+        han += h;
+        if h > 0 {
+            names.push(name);
+        }
+    }
+    for &id in &yakuman {
+        names.push(
+            TENHOU_YAKU
+                .get(id as usize)
+                .map(|y| y.1)
+                .unwrap_or("不明な役"),
+        );
+    }
+    let ten = attr_nums(attrs, "ten")?;
+    let sc = attr_nums(attrs, "sc")?;
+
+    Ok(MjlogWin {
+        winner,
+        loser,
+        pao,
+        request,
+        log: WinSummary {
+            han,
+            fu: ten.first().map(|&f| f as u32),
+            yakuman: yakuman.len() as u32,
+            yaku: summary_yaku(names.into_iter()),
+            deltas: sc
+                .chunks(2)
+                .map(|c| c.get(1).copied().unwrap_or(0) as i32 * 100)
+                .collect(),
+        },
+    })
+}
+
+// ===== tenhou.net/6 JSON =====
+
+/// 牌（11〜19 萬子、21〜29 筒子、31〜39 索子、41〜47 字牌、51〜53 赤五）
+fn json_tile(n: i64) -> Result<Tile, String> {
+    let kind = match n {
+        11..=19 => n - 11,
+        21..=29 => n - 12,
+        31..=39 => n - 13,
+        41..=47 => n - 14,
+        51..=53 => (n - 51) * 9 + 4,
+        _ => return Err(format!("牌番号が不正です: {}", n)),
+    };
+    let mut t = TileKey::all()[kind as usize].to_tile();
+    t.red = n >= 51;
+    Ok(t)
+}
+
+fn json_tiles(v: &Value) -> Result<Vec<Tile>, String> {
+    v.as_array()
+        .ok_or("牌の配列がありません")?
+        .iter()
+        .map(|t| json_tile(t.as_i64().ok_or("牌番号が不正です")?))
+        .collect()
+}
+
+/// 鳴きの文字列（"c275226" / "17p1717" / "393939m39" / "424242a42" など）
+/// - 返り値: (種類の文字、文字の位置、牌)
+fn parse_call(s: &str) -> Result<(char, usize, Vec<Tile>), String> {
+    let (pos, c) = s
+        .char_indices()
+        .find(|(_, c)| c.is_ascii_alphabetic())
+        .ok_or_else(|| format!("鳴きの表記が不正です: {}", s))?;
+    let digits: String = s.chars().filter(|c| c.is_ascii_digit()).collect();
+    let tiles = digits
+        .as_bytes()
+        .chunks(2)
+        .map(|d| {
+            std::str::from_utf8(d)
+                .ok()
+                .and_then(|d| d.parse().ok())
+                .ok_or_else(|| format!("鳴きの表記が不正です: {}", s))
+                .and_then(json_tile)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if tiles.is_empty() {
+        return Err(format!("鳴きの表記に牌がありません: {}", s));
+    }
+    Ok((c, pos, tiles))
+}
+
+fn import_json(text: &str) -> Result<Vec<MjlogRound>, String> {
+    let v: Value =
+        serde_json::from_str(text).map_err(|e| format!("JSON の読み込みに失敗しました: {}", e))?;
+    let rule = &v["rule"];
+    let disp = rule["disp"].as_str().unwrap_or("");
+    let aka = ["aka", "aka51", "aka52", "aka53"]
+        .iter()
+        .any(|k| rule[k].as_i64().unwrap_or(0) > 0);
+    let kuitan = disp.is_empty() || disp.contains('喰');
+    let logs = v["log"].as_array().ok_or("log がありません")?;
+
+    let mut rounds = vec![];
+    for log in logs {
+        let log = log.as_array().ok_or("log の局が配列ではありません")?;
+        let players = (0..4)
+            .filter(|i| {
+                log.get(4 + 3 * i)
+                    .and_then(|h| h.as_array())
+                    .is_some_and(|h| !h.is_empty())
+            })
+            .count();
+        if !(3..=4).contains(&players) {
+            return Err(format!("配牌の人数が不正です: {}", players));
+        }
+        let rules = tenhou_rules(aka, kuitan, players == 3);
+        let round = json_round(log, &rules, players)?;
+        rounds.push(round);
+    }
+    Ok(rounds)
+}
+
+fn json_round(log: &[Value], rules: &RuleSet, players: usize) -> Result<MjlogRound, String> {
+    let head = log
+        .first()
+        .and_then(|h| h.as_array())
+        .ok_or("局の情報がありません")?;
+    let num = |i: usize| head.get(i).and_then(|n| n.as_i64()).unwrap_or(0);
+    let (kyoku, honba, kyotaku) = (num(0) as usize, num(1) as u32, num(2) as u32);
+    let label = round_label(kyoku, honba);
+    let at = |e: String| format!("{}: {}", label, e);
+    let dealer = kyoku % 4 % players;
+
+    let mut hands = vec![];
+    let mut takes = vec![];
+    let mut dahai = vec![];
+    for seat in 0..players {
+        let get = |i: usize| {
+            log.get(4 + 3 * seat + i)
+                .ok_or("配牌・ツモ・打牌がありません")
+        };
+        hands.push(json_tiles(get(0)?).map_err(at)?);
+        takes.push(get(1)?.as_array().cloned().unwrap_or_default());
+        dahai.push(get(2)?.as_array().cloned().unwrap_or_default());
+    }
+
+    let mut r = Replay::new(dealer, hands);
+    let (mut ti, mut di) = (vec![0; players], vec![0; players]);
+    let mut cur = dealer;
+    let mut need_draw = true;
+    let mut drawn: Option<Tile> = None;
+    // この局で成立した立直を含む供託（立直宣言牌が通ったら1本増える）
+    let mut kyotaku_now = kyotaku;
+    let mut reach_pending = false;
+
+    'play: loop {
+        if need_draw {
+            let Some(t) = takes[cur].get(ti[cur]) else {
+                break;
+            };
+            if std::mem::take(&mut reach_pending) {
+                kyotaku_now += 1;
+            }
+            let tile = json_tile(
+                t.as_i64()
+                    .ok_or_else(|| at(format!("ツモの表記が不正です: {}", t)))?,
+            )
+            .map_err(at)?;
+            ti[cur] += 1;
+            r.draw(cur, tile);
+            drawn = Some(tile);
+        }
+        let Some(d) = dahai[cur].get(di[cur]) else {
+            break;
+        };
+        di[cur] += 1;
+
+        let mut declared = false;
+        let tile = match d {
+            Value::Number(n) => {
+                let n = n.as_i64().unwrap_or(0);
+                match n {
+                    // 大明槓の後の空き
+                    0 => {
+                        need_draw = true;
+                        continue;
+                    }
+                    60 => drawn.ok_or_else(|| at("ツモ切りする牌がありません".into()))?,
+                    _ => json_tile(n).map_err(at)?,
+                }
+            }
+            Value::String(s) if s.starts_with('r') => {
+                r.reach[cur] = true;
+                declared = true;
+                match s[1..].parse::<i64>() {
+                    Ok(60) => drawn.ok_or_else(|| at("ツモ切りする牌がありません".into()))?,
+                    Ok(n) => json_tile(n).map_err(at)?,
+                    Err(_) => return Err(at(format!("立直の表記が不正です: {}", s))),
+                }
+            }
+            Value::String(s) => {
+                let (c, pos, tiles) = parse_call(s).map_err(at)?;
+                match c {
+                    'a' => r.kan(cur, meld(MeldType::ANKAN, &tiles)),
+                    'k' => {
+                        let idx = (pos / 2).min(tiles.len() - 1);
+                        let mut all = tiles.clone();
+                        let added = all.remove(idx);
+                        all.push(added);
+                        r.kan(cur, meld(MeldType::MINKAN, &all))
+                    }
+                    'f' => r.nuki(cur, tiles[0]),
+                    _ => Err(format!("打牌の表記が不正です: {}", s)),
+                }
+                .map_err(at)?;
+                need_draw = true;
+                continue;
+            }
+            _ => return Err(at(format!("打牌の表記が不正です: {}", d))),
+        };
+        r.discard(cur, tile).map_err(at)?;
+        drawn = None;
+        reach_pending = declared;
+
+        // ポン・大明槓をチーより優先して、この打牌を鳴いた席を探す
+        let mut call = None;
+        for step in 1..players {
+            let seat = (cur + step) % players;
+            let Some(Value::String(s)) = takes[seat].get(ti[seat]) else {
+                continue;
+            };
+            let (c, pos, tiles) = parse_call(s).map_err(at)?;
+            let rel = match (c, pos) {
+                ('c', _) | ('p', 0) | ('m', 0) => players - 1,
+                ('p', 2) | ('m', 2) => 2,
+                _ => 1,
+            };
+            let idx = if c == 'c' {
+                0
+            } else {
+                (pos / 2).min(tiles.len() - 1)
+            };
+            if (seat + rel) % players != cur || tiles.get(idx) != Some(&tile) {
+                continue;
+            }
+            let better = call
+                .as_ref()
+                .is_none_or(|(_, prev, _, _): &(usize, char, usize, Vec<Tile>)| *prev == 'c');
+            if better {
+                call = Some((seat, c, idx, tiles));
+            }
+        }
+        let Some((seat, c, idx, tiles)) = call else {
+            cur = (cur + 1) % players;
+            need_draw = true;
+            continue 'play;
+        };
+        ti[seat] += 1;
+        if std::mem::take(&mut reach_pending) {
+            kyotaku_now += 1;
+        }
+        let called = tiles[idx];
+        let meld_type = match c {
+            'c' => MeldType::CHI,
+            'p' => MeldType::PON,
+            'm' => MeldType::MINKAN,
+            _ => return Err(at(format!("鳴きの表記が不正です: {}", c))),
+        };
+        let mut sorted = tiles.clone();
+        if meld_type == MeldType::CHI {
+            sorted.sort_by_key(|t| t.num);
+        }
+        r.call(seat, cur, meld(meld_type, &sorted), called)
+            .map_err(at)?;
+        cur = seat;
+        need_draw = false;
+    }
+
+    let mut round = MjlogRound {
+        label: label.clone(),
+        round_wind: wind(kyoku / 4),
+        dealer,
+        honba,
+        kyotaku,
+        events: r.events.clone(),
+        wins: vec![],
+    };
+
+    let result = log
+        .get(16)
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| at("結果がありません".into()))?;
+    if result.first().and_then(|v| v.as_str()) != Some("和了") {
+        return Ok(round);
+    }
+    let dora = json_tiles(&log[2]).map_err(at)?;
+    let ura = json_tiles(&log[3]).map_err(at)?;
+    for pair in result[1..].chunks(2) {
+        let [deltas, info] = pair else {
+            continue;
+        };
+        let info = info
+            .as_array()
+            .ok_or_else(|| at("和了情報が不正です".into()))?;
+        let who = |i: usize| info.get(i).and_then(|v| v.as_u64()).map(|v| v as usize);
+        let winner = r.seat(who(0).unwrap_or(usize::MAX)).map_err(at)?;
+        let from = r.seat(who(1).unwrap_or(usize::MAX)).map_err(at)?;
+        let loser = (from != winner).then_some(from);
+        let pao = who(2).filter(|&p| p != winner);
+        let win_tile = match loser {
+            None => match r.events.last() {
+                Some(RoundEvent::DRAW { tile, .. }) => *tile,
+                _ => return Err(at("ツモ和了の牌がありません".into())),
+            },
+            Some(_) => r
+                .last_tile
+                .ok_or_else(|| at("ロン和了の牌がありません".into()))?,
+        };
+        let request = r
+            .request(
+                rules,
+                round.round_wind,
+                dealer,
+                honba,
+                kyotaku_now,
+                winner,
+                loser,
+                win_tile,
+                &dora,
+                &ura,
+            )
+            .map_err(at)?;
+        // 2人目以降のロンに供託は付かない
+        let kyotaku_here = if round.wins.is_empty() {
+            kyotaku_now
+        } else {
+            0
+        };
+        let request = ScoreRequest {
+            kyotaku: kyotaku_here,
+            ..request
+        };
+
+        let mut han = 0;
+        let mut yakuman = 0;
+        let mut names = vec![];
+        for y in info.iter().skip(4).filter_map(|v| v.as_str()) {
+            let (name, value) = y.split_once('(').unwrap_or((y, ""));
+            if value.starts_with("役満") {
+                yakuman += 1;
+            } else {
+                let h: u32 = value
+                    .trim_end_matches(')')
+                    .trim_end_matches('飜')
+                    .parse()
+                    .unwrap_or(0);
+                han += h;
+                if h == 0 {
+                    continue;
+                }
+            }
+            names.push(our_yaku_name(name));
+        }
+        let points = info.get(3).and_then(|v| v.as_str()).unwrap_or("");
+        let fu = points.split_once('符').and_then(|(f, _)| f.parse().ok());
+
+        round.wins.push(MjlogWin {
+            winner,
+            loser,
+            pao,
+            request,
+            log: WinSummary {
+                han,
+                fu,
+                yakuman,
+                yaku: summary_yaku(names.into_iter()),
+                deltas: deltas
+                    .as_array()
+                    .map(|d| {
+                        d.iter()
+                            .take(players)
+                            .map(|x| x.as_i64().unwrap_or(0) as i32)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            },
+        });
+    }
+    Ok(round)
+}
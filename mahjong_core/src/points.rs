@@ -1,9 +1,38 @@
+use crate::rules::{KazoeRule, RuleSet, SanmaTsumo};
 use crate::WinType;
+use serde::{Deserialize, Serialize};
+
+/// 満貫以上の区分
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum LimitTier {
+    NONE,
+    /// 切り上げ満貫（4翻30符・3翻60符）
+    KIRIAGE,
+    MANGAN,
+    HANEMAN,
+    BAIMAN,
+    SANBAIMAN,
+    KAZOE,
+    YAKUMAN,
+}
 
 #[derive(Debug, Clone)]
 pub struct PointBreakdown {
     pub total_points: u32,
+    pub limit: LimitTier,
     pub payments: Vec<String>, // e.g. ["親ロン: 12000", "本場: +300", ...]
+
+    // 1人あたりの支払い（本場・供託を除く）
+    pub ron: u32,              // ロン：放銃者
+    pub tsumo_dealer: u32,     // 子ツモ：親
+    pub tsumo_non_dealer: u32, // ツモ：子（親ツモなら全員）
+
+    pub honba: u32,
+    /// 積み棒の支払い（ロンの放銃者 / ツモの1人あたり）
+    pub honba_ron: u32,
+    pub honba_tsumo: u32,
+    pub kyotaku: u32,
 }
 
 /// Japanese Riichi Mahjong point calc (rounded up to 100).
@@ -11,8 +40,10 @@ pub struct PointBreakdown {
 /// - base points from fu/han with mangan+ caps
 /// - dealer/non-dealer
 /// - ron/tsumo
-/// - honba (300/100 per honba)
+/// - honba (rules.honba_points per honba: 300 on ron, 100 from each on tsumo by default)
 /// - kyotaku (1000 per stick added to winner)
+/// - ruleset options (kazoe yakuman or sanbaiman cap, kiriage mangan)
+#[allow(clippy::too_many_arguments)]
 pub fn calc_points(
     fu: u32,
     han: u32,
@@ -21,32 +52,43 @@ pub fn calc_points(
     dealer: bool,
     honba: u32,
     kyotaku: u32,
+    rules: &RuleSet,
 ) -> PointBreakdown {
     let mut payments = vec![];
 
-    let mut total = 0u32;
+    let mut total: u32;
+    let limit: LimitTier;
+    let mut ron = 0;
+    let mut tsumo_dealer = 0;
+    let mut tsumo_non_dealer = 0;
+
+    // ツモで支払う人数（三麻は2人）
+    let payers = rules.players() as u32 - 1;
 
     if yakuman > 0 {
         // yakuman base: dealer 48000, non-dealer 32000 total (ron); tsumo split
         let base_total = if dealer { 48000 } else { 32000 };
         let base_total = base_total * yakuman;
+        limit = LimitTier::YAKUMAN;
 
         match win_type {
             WinType::RON => {
                 total = base_total;
+                ron = base_total;
                 payments.push(format!("役満{}倍 ロン: {}", yakuman, base_total));
             }
             WinType::TSUMO => {
+                // base 8000*y: dealer pays 16000*y, others 8000*y (each pays 16000*y on dealer tsumo)
+                let (from_dealer, from_other) = tsumo_shares(8000 * yakuman, dealer, rules);
                 if dealer {
-                    // each pays 16000 * yakuman
-                    let each = 16000 * yakuman;
-                    total = each * 3;
+                    let each = from_other;
+                    total = each * payers;
+                    tsumo_non_dealer = each;
                     payments.push(format!("役満{}倍 親ツモ: {}オール", yakuman, each));
                 } else {
-                    // dealer pays 16000*y, others 8000*y
-                    let from_dealer = 16000 * yakuman;
-                    let from_other = 8000 * yakuman;
-                    total = from_dealer + from_other * 2;
+                    total = from_dealer + from_other * (payers - 1);
+                    tsumo_dealer = from_dealer;
+                    tsumo_non_dealer = from_other;
                     payments.push(format!(
                         "役満{}倍 子ツモ: 親{} / 子{}",
                         yakuman, from_dealer, from_other
@@ -62,21 +104,27 @@ pub fn calc_points(
         // haneman: 3000
         // baiman: 4000
         // sanbaiman: 6000
-        // kazoe yakuman: 8000 (13+ han)
-        let capped_base = if han >= 13 {
-            8000
+        // kazoe yakuman: 8000 (13+ han), or sanbaiman when the ruleset caps kazoe
+        let (capped_base, tier) = if han >= 13 && rules.kazoe == KazoeRule::YAKUMAN {
+            (8000, LimitTier::KAZOE)
         } else if han >= 11 {
-            6000
+            (6000, LimitTier::SANBAIMAN)
         } else if han >= 8 {
-            4000
+            (4000, LimitTier::BAIMAN)
         } else if han >= 6 {
-            3000
+            (3000, LimitTier::HANEMAN)
         } else if han == 5 || (han == 4 && fu >= 40) || (han == 3 && fu >= 70) {
-            2000
+            (2000, LimitTier::MANGAN)
+        } else if rules.kiriage && ((han == 4 && fu == 30) || (han == 3 && fu == 60)) {
+            (2000, LimitTier::KIRIAGE)
         } else {
             // round base up? base itself is not rounded; payments are rounded
-            base as u32
+            (base as u32, LimitTier::NONE)
         };
+        limit = tier;
+        if tier == LimitTier::KIRIAGE {
+            payments.push(format!("切り上げ満貫: {}翻{}符", han, fu));
+        }
 
         match win_type {
             WinType::RON => {
@@ -85,28 +133,37 @@ pub fn calc_points(
                 } else {
                     capped_base * 4
                 };
-                let ron = round_up_100(raw);
+                ron = round_up_100(raw);
                 total = ron;
                 payments.push(format!("ロン: {}", ron));
             }
             WinType::TSUMO => {
+                let (from_dealer, from_other) = tsumo_shares(capped_base, dealer, rules);
                 if dealer {
-                    let each = round_up_100(capped_base * 2);
-                    total = each * 3;
+                    let each = round_up_100(from_other);
+                    total = each * payers;
+                    tsumo_non_dealer = each;
                     payments.push(format!("親ツモ: {}オール", each));
                 } else {
-                    let from_dealer = round_up_100(capped_base * 2);
-                    let from_other = round_up_100(capped_base * 1);
-                    total = from_dealer + from_other * 2;
+                    let from_dealer = round_up_100(from_dealer);
+                    let from_other = round_up_100(from_other);
+                    total = from_dealer + from_other * (payers - 1);
+                    tsumo_dealer = from_dealer;
+                    tsumo_non_dealer = from_other;
                     payments.push(format!("子ツモ: 親{} / 子{}", from_dealer, from_other));
                 }
             }
         }
     }
 
-    // honba: ron +300/honba, tsumo each +100/honba (total +300/honba)
-    if honba > 0 {
-        let add = 300 * honba;
+    // honba: ron +300/honba, tsumo each +100/honba (total +300/honba, sanma +200/honba)
+    let honba_ron = rules.honba_points * honba;
+    let honba_tsumo = rules.honba_points / 3 * honba;
+    if honba_ron > 0 {
+        let add = match win_type {
+            WinType::RON => honba_ron,
+            WinType::TSUMO => honba_tsumo * payers,
+        };
         total += add;
         payments.push(format!("本場 +{}", add));
     }
@@ -120,10 +177,234 @@ pub fn calc_points(
 
     PointBreakdown {
         total_points: total,
+        limit,
         payments,
+        ron,
+        tsumo_dealer,
+        tsumo_non_dealer,
+        honba,
+        honba_ron,
+        honba_tsumo,
+        kyotaku,
+    }
+}
+
+/// 和了による席ごとの点数移動（本場・供託込み）
+/// - loser: None ならツモ（winner 以外の全員が支払う）
+pub fn payment_deltas(
+    bd: &PointBreakdown,
+    players: usize,
+    dealer: usize,
+    winner: usize,
+    loser: Option<usize>,
+) -> Vec<i32> {
+    let mut deltas = vec![0i32; players];
+    match loser {
+        Some(l) => {
+            let pay = (bd.ron + bd.honba_ron) as i32;
+            deltas[l] -= pay;
+            deltas[winner] += pay;
+        }
+        None => {
+            for seat in 0..players {
+                if seat == winner {
+                    continue;
+                }
+                let base = if seat == dealer {
+                    bd.tsumo_dealer
+                } else {
+                    bd.tsumo_non_dealer
+                };
+                let pay = (base + bd.honba_tsumo) as i32;
+                deltas[seat] -= pay;
+                deltas[winner] += pay;
+            }
+        }
+    }
+    deltas[winner] += 1000 * bd.kyotaku as i32;
+    deltas
+}
+
+/// チョンボの罰符（満貫ツモの逆：違反者がほかの全員へ支払う。本場・供託なし）
+pub fn chombo_deltas(players: usize, dealer: usize, offender: usize, rules: &RuleSet) -> Vec<i32> {
+    let bd = calc_points(0, 5, 0, WinType::TSUMO, offender == dealer, 0, 0, rules);
+    payment_deltas(&bd, players, dealer, offender, None)
+        .into_iter()
+        .map(|d| -d)
+        .collect()
+}
+
+/// 荒牌流局の不聴罰符（場に3000点）
+pub fn draw_deltas(tenpai: &[bool]) -> Vec<i32> {
+    let mut deltas = vec![0i32; tenpai.len()];
+    let n_tenpai = tenpai.iter().filter(|&&t| t).count() as i32;
+    let n_noten = tenpai.len() as i32 - n_tenpai;
+    if n_tenpai > 0 && n_noten > 0 {
+        for (seat, &t) in tenpai.iter().enumerate() {
+            deltas[seat] = if t { 3000 / n_tenpai } else { -3000 / n_noten };
+        }
+    }
+    deltas
+}
+
+/// 点数表に現れる (翻, 符) の組を翻→符の順で返す
+/// - 1〜4翻は符ごと（ロンの20符、1翻の25符、ツモの2翻25符は存在しない）
+/// - 5翻以上は符に依らないので fu = 0
+pub fn han_fu_candidates(win_type: WinType) -> Vec<(u32, u32)> {
+    let mut v = vec![];
+    for han in 1..=4 {
+        if han >= 2 && win_type == WinType::TSUMO {
+            v.push((han, 20));
+        }
+        let chiitoi_min = if win_type == WinType::TSUMO { 3 } else { 2 };
+        if han >= chiitoi_min {
+            v.push((han, 25));
+        }
+        for fu in (30..=110).step_by(10) {
+            v.push((han, fu));
+        }
+    }
+    for han in 5..=13 {
+        v.push((han, 0));
+    }
+    v
+}
+
+/// ツモの1人あたり支払い（丸め前）: (親から, 子から)
+/// - 親ツモは子から 2b ずつ（親からは 0）、子ツモは親 2b / 子 b
+/// - 三麻の折半ルールでは不在者の分（親ツモ 2b / 子ツモ b）を2人で分ける
+fn tsumo_shares(base: u32, dealer: bool, rules: &RuleSet) -> (u32, u32) {
+    let split = rules.sanma && rules.sanma_tsumo == SanmaTsumo::SPLIT;
+    match (dealer, split) {
+        (true, false) => (0, base * 2),
+        (true, true) => (0, base * 3),
+        (false, false) => (base * 2, base),
+        (false, true) => (base * 2 + base / 2, base + base / 2),
     }
 }
 
 fn round_up_100(x: u32) -> u32 {
-    ((x + 99) / 100) * 100
+    x.div_ceil(100) * 100
+}
+
+/// 逆引きする支払い（本場込み・供託抜き）
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Payment {
+    /// 和了者の受け取り総額（ロンの支払い / ツモの合計）
+    TOTAL(u32),
+    /// ツモの1人あたり。子ツモは「子-親」、親ツモは non_dealer にオール（dealer は 0）
+    SPLIT { non_dealer: u32, dealer: u32 },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct HanFu {
+    pub han: u32,
+    /// 5翻以上・役満は 0
+    pub fu: u32,
+    pub yakuman: u32,
+}
+
+/// calc_points の逆引き：支払いになる (翻, 符) / 役満倍数をすべて列挙する
+pub fn lookup_han_fu(
+    payment: Payment,
+    dealer: bool,
+    win_type: WinType,
+    honba: u32,
+    rules: &RuleSet,
+) -> Vec<HanFu> {
+    let candidates = han_fu_candidates(win_type)
+        .into_iter()
+        .map(|(han, fu)| HanFu {
+            han,
+            fu,
+            yakuman: 0,
+        })
+        .chain((1..=6).map(|yakuman| HanFu {
+            han: 0,
+            fu: 0,
+            yakuman,
+        }));
+
+    candidates
+        .filter(|c| {
+            let bd = calc_points(c.fu, c.han, c.yakuman, win_type, dealer, honba, 0, rules);
+            match payment {
+                Payment::TOTAL(total) => bd.total_points == total,
+                Payment::SPLIT {
+                    non_dealer,
+                    dealer: from_dealer,
+                } => {
+                    win_type == WinType::TSUMO
+                        && bd.tsumo_non_dealer + bd.honba_tsumo == non_dealer
+                        && if dealer {
+                            from_dealer == 0
+                        } else {
+                            bd.tsumo_dealer + bd.honba_tsumo == from_dealer
+                        }
+                }
+            }
+        })
+        .collect()
+}
+
+/// 点数表の1行（該当しない和了形は None）
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreTableRow {
+    /// 役満の行は 0
+    pub han: u32,
+    /// 5翻以上・役満は 0
+    pub fu: u32,
+    /// 役満の倍数（役満でない行は 0）
+    pub yakuman: u32,
+    pub limit: LimitTier,
+    pub dealer_ron: Option<u32>,
+    pub dealer_tsumo_all: Option<u32>,
+    pub non_dealer_ron: Option<u32>,
+    pub non_dealer_tsumo_dealer: Option<u32>,
+    pub non_dealer_tsumo_non_dealer: Option<u32>,
+}
+
+/// calc_points から生成する符×翻の点数表（本場・供託なし）
+/// - 最後に役満（1倍から rules.yakuman.cap 倍まで）の行を付ける
+pub fn score_table(rules: &RuleSet) -> Vec<ScoreTableRow> {
+    let ron = han_fu_candidates(WinType::RON);
+    let tsumo = han_fu_candidates(WinType::TSUMO);
+
+    let mut keys: Vec<(u32, u32)> = ron.iter().chain(tsumo.iter()).copied().collect();
+    keys.sort();
+    keys.dedup();
+
+    let row = |han: u32, fu: u32, yakuman: u32, has_ron: bool, has_tsumo: bool| {
+        let calc = |win_type, dealer| calc_points(fu, han, yakuman, win_type, dealer, 0, 0, rules);
+        let dr = calc(WinType::RON, true);
+        let dt = calc(WinType::TSUMO, true);
+        let nr = calc(WinType::RON, false);
+        let nt = calc(WinType::TSUMO, false);
+
+        ScoreTableRow {
+            han,
+            fu,
+            yakuman,
+            limit: nr.limit,
+            dealer_ron: has_ron.then_some(dr.ron),
+            dealer_tsumo_all: has_tsumo.then_some(dt.tsumo_non_dealer),
+            non_dealer_ron: has_ron.then_some(nr.ron),
+            non_dealer_tsumo_dealer: has_tsumo.then_some(nt.tsumo_dealer),
+            non_dealer_tsumo_non_dealer: has_tsumo.then_some(nt.tsumo_non_dealer),
+        }
+    };
+
+    keys.iter()
+        .map(|&(han, fu)| {
+            row(
+                han,
+                fu,
+                0,
+                ron.contains(&(han, fu)),
+                tsumo.contains(&(han, fu)),
+            )
+        })
+        .chain((1..=rules.yakuman.cap).map(|y| row(0, 0, y, true, true)))
+        .collect()
 }
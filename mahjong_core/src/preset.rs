@@ -0,0 +1,163 @@
+use crate::rules::{KazoeRule, RuleSet, YakumanRules, RULES_VERSION};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// 公開ルールブックに合わせたルールセット
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RulePreset {
+    /// 天鳳（段位戦）
+    TENHOU,
+    /// 雀魂（段位戦）
+    MAHJONGSOUL,
+    /// World Riichi Championship
+    WRC,
+    /// 日本プロ麻雀連盟 Aルール
+    #[serde(rename = "jpml_a")]
+    JPMLA,
+    /// 日本プロ麻雀連盟 Bルール
+    #[serde(rename = "jpml_b")]
+    JPMLB,
+    /// European Mahjong Association（裏ドラ・赤ドラなし）
+    EMA,
+}
+
+impl RulePreset {
+    pub fn from_name(name: &str) -> Result<RulePreset, String> {
+        serde_json::from_value(Value::String(name.to_lowercase()))
+            .map_err(|_| format!("不明なルールプリセットです: {}", name))
+    }
+
+    pub fn rules(self) -> RuleSet {
+        let base = RuleSet::default();
+        match self {
+            RulePreset::TENHOU => RuleSet {
+                tenpai_yame: false,
+                yakuman: single_yakuman(true, 6),
+                ..base
+            },
+            RulePreset::MAHJONGSOUL => RuleSet {
+                return_points: 25000,
+                uma: vec![15, 5, -5, -15],
+                ..base
+            },
+            RulePreset::WRC => RuleSet {
+                start_points: 30000,
+                return_points: 30000,
+                uma: vec![15, 5, -5, -15],
+                tobi: false,
+                agari_yame: false,
+                tenpai_yame: false,
+                extension: false,
+                aka_dora: 0,
+                kiriage: true,
+                kazoe: KazoeRule::SANBAIMAN,
+                yakuman: single_yakuman(false, 1),
+                ..base
+            },
+            RulePreset::JPMLA => RuleSet {
+                start_points: 30000,
+                return_points: 30000,
+                uma: vec![15, 5, -5, -15],
+                tobi: false,
+                extension: false,
+                ippatsu: false,
+                ura_dora: false,
+                kan_dora: false,
+                aka_dora: 0,
+                kazoe: KazoeRule::SANBAIMAN,
+                yakuman: single_yakuman(false, 1),
+                ..base
+            },
+            RulePreset::JPMLB => RuleSet {
+                start_points: 30000,
+                return_points: 30000,
+                uma: vec![15, 5, -5, -15],
+                tobi: false,
+                extension: false,
+                aka_dora: 0,
+                kiriage: true,
+                yakuman: single_yakuman(false, 1),
+                ..base
+            },
+            RulePreset::EMA => RuleSet {
+                start_points: 30000,
+                return_points: 30000,
+                uma: vec![15, 5, -5, -15],
+                tobi: false,
+                agari_yame: false,
+                tenpai_yame: false,
+                extension: false,
+                ura_dora: false,
+                aka_dora: 0,
+                honba_points: 0,
+                kazoe: KazoeRule::SANBAIMAN,
+                yakuman: single_yakuman(true, 6),
+                ..base
+            },
+        }
+    }
+}
+
+/// ダブル役満なし（複合と上限だけ指定）
+fn single_yakuman(stacking: bool, cap: u32) -> YakumanRules {
+    YakumanRules {
+        multipliers: BTreeMap::new(),
+        stacking,
+        cap,
+    }
+}
+
+/// ルールの入力形式
+/// - オブジェクト: RuleSet（省略した項目は既定値）
+/// - 文字列: プリセット名（例: "ema"）
+/// - "preset" を含むオブジェクト: プリセットに残りの項目を上書き
+pub fn rules_from_value(value: Value) -> Result<RuleSet, String> {
+    rules_over(&RuleSet::default(), value)
+}
+
+/// rules_from_value と同じ形式で、省略した項目を base から取る（サーバーのルールへの部分上書きなど）
+pub fn rules_over(base: &RuleSet, value: Value) -> Result<RuleSet, String> {
+    let (base, overrides) = match value {
+        Value::Null => return Ok(base.clone()),
+        Value::String(name) => return Ok(RulePreset::from_name(&name)?.rules()),
+        Value::Object(mut obj) => match obj.remove("preset") {
+            Some(Value::String(name)) => (RulePreset::from_name(&name)?.rules(), obj),
+            Some(_) => return Err("preset はプリセット名の文字列で指定してください".into()),
+            None => (base.clone(), obj),
+        },
+        _ => return Err("rules はオブジェクトかプリセット名で指定してください".into()),
+    };
+
+    let mut merged = serde_json::to_value(base).map_err(|e| e.to_string())?;
+    merge(&mut merged, overrides);
+    let rules: RuleSet =
+        serde_json::from_value(merged).map_err(|e| format!("ルールの指定が不正です: {}", e))?;
+    if rules.version > RULES_VERSION {
+        return Err(format!(
+            "ルールのバージョン({})に対応していません（{}まで）",
+            rules.version, RULES_VERSION
+        ));
+    }
+    Ok(rules)
+}
+
+/// serde の deserialize_with 用
+pub fn deserialize_rules<'de, D: Deserializer<'de>>(d: D) -> Result<RuleSet, D::Error> {
+    rules_from_value(Value::deserialize(d)?).map_err(serde::de::Error::custom)
+}
+
+fn merge(base: &mut Value, overrides: Map<String, Value>) {
+    let Value::Object(obj) = base else {
+        return;
+    };
+    for (k, v) in overrides {
+        match (obj.get_mut(&k), v) {
+            (Some(slot @ Value::Object(_)), Value::Object(inner)) => merge(slot, inner),
+            (_, v) => {
+                obj.insert(k, v);
+            }
+        }
+    }
+}
@@ -0,0 +1,724 @@
+use crate::ankan::{check_riichi_ankan, AnkanVerdict};
+use crate::calls::{call_melds, kan_melds, kuikae, meld_key, meld_tiles};
+use crate::flags::derive_flags;
+use crate::furiten::{ron_furiten, FuritenInfo};
+use crate::rules::RuleSet;
+use crate::score::score;
+use crate::tile::{Suit, Tile, TileKey};
+use crate::wait::{is_complete, is_tenpai};
+use crate::{Meld, MeldType, Riichi, RobbedKan, ScoreRequest, ScoreResult, WinType, Wind};
+use serde::{Deserialize, Serialize};
+
+/// 王牌の枚数
+const DEAD_WALL: usize = 14;
+
+/// プレイヤーの行動
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "UPPERCASE")]
+pub enum Action {
+    /// 打牌（riichi: 立直宣言牌）
+    DISCARD {
+        tile: Tile,
+        riichi: bool,
+    },
+    /// 捨て牌へのチー・ポン・明槓（tiles は鳴いた牌を含む）
+    CALL {
+        meld: Meld,
+    },
+    /// 自分の手番の暗槓（ANKAN）・加槓（MINKAN）
+    KAN {
+        meld: Meld,
+    },
+    TSUMO,
+    RON,
+    PASS,
+}
+
+/// 局の進行で起きたこと（起家からの席番号）
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "UPPERCASE")]
+pub enum RoundEvent {
+    /// 配牌直後（wall: ツモれる山の枚数）
+    START {
+        dealer: usize,
+        wall: usize,
+    },
+    DRAW {
+        seat: usize,
+        tile: Tile,
+        rinshan: bool,
+    },
+    DISCARD {
+        seat: usize,
+        tile: Tile,
+        riichi: bool,
+    },
+    CALL {
+        seat: usize,
+        from: usize,
+        meld: Meld,
+    },
+    KAN {
+        seat: usize,
+        meld: Meld,
+    },
+    DORA {
+        indicator: Tile,
+    },
+}
+
+/// 局の結果
+#[derive(Debug, Serialize)]
+pub enum RoundEnd {
+    WIN {
+        winner: usize,
+        /// None ならツモ
+        loser: Option<usize>,
+        request: Box<ScoreRequest>,
+        result: ScoreResult,
+    },
+    /// 荒牌流局（席ごとの聴牌）
+    DRAW { tenpai: Vec<bool> },
+}
+
+#[derive(Debug, Serialize)]
+pub enum Phase {
+    /// seat の打牌待ち（drawn: ツモった牌。鳴いた直後は None）
+    TURN {
+        seat: usize,
+        drawn: Option<Tile>,
+        rinshan: bool,
+    },
+    /// 打牌・槓への応答待ち（responses: 席ごとの応答。None は未応答）
+    CLAIM {
+        from: usize,
+        tile: Tile,
+        /// 槓への搶槓待ちのとき、その槓
+        kan: Option<Meld>,
+        responses: Vec<Option<Action>>,
+    },
+    ENDED(RoundEnd),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayerState {
+    /// 副露を除いた手牌
+    pub hand: Vec<Tile>,
+    pub melds: Vec<Meld>,
+    pub discards: Vec<Tile>,
+    pub riichi: Riichi,
+    /// まだ第一打をしていない
+    pub first_turn: bool,
+}
+
+/// 1局の進行（山・王牌・配牌・ツモ・打牌・鳴き・嶺上・流局）
+/// - 山は seed から決まる（同じ seed なら同じ配牌）
+/// - 和了すると ScoreRequest を組み立てて score を呼ぶ
+#[derive(Debug, Serialize)]
+pub struct Round {
+    pub rules: RuleSet,
+    pub round_wind: Wind,
+    pub dealer: usize,
+    pub honba: u32,
+    /// 供託（この局で成立した立直を含む）
+    pub kyotaku: u32,
+    pub players: Vec<PlayerState>,
+    /// 配牌（席順。親の第一ツモを含まない）
+    pub haipai: Vec<Vec<Tile>>,
+    pub phase: Phase,
+    pub events: Vec<RoundEvent>,
+    /// 生きている山（先頭からツモる）
+    wall: Vec<Tile>,
+    /// 王牌 14枚：[0..4] 嶺上牌、[4..9] ドラ表示牌、[9..14] 裏ドラ表示牌
+    dead_wall: Vec<Tile>,
+    kans: usize,
+    /// 鳴き（暗槓を含む）で第一巡が途切れた
+    interrupted: bool,
+    /// 立直宣言牌が通るまで成立を保留する席
+    pending_riichi: Option<usize>,
+    /// 鳴いた直後に切れない牌（喰い替え）
+    #[serde(skip)]
+    kuikae: Vec<TileKey>,
+}
+
+impl Round {
+    pub fn new(
+        rules: RuleSet,
+        round_wind: Wind,
+        dealer: usize,
+        honba: u32,
+        kyotaku: u32,
+        seed: u64,
+    ) -> Result<Round, String> {
+        if rules.sanma {
+            return Err("三麻の局進行には対応していません".into());
+        }
+        let players = rules.players();
+        if dealer >= players {
+            return Err(format!("親の席が不正です: {}", dealer));
+        }
+
+        let mut tiles = build_tiles(rules.aka_dora);
+        shuffle(&mut tiles, seed);
+        let dead_wall = tiles.split_off(tiles.len() - DEAD_WALL);
+
+        let mut round = Round {
+            rules,
+            round_wind,
+            dealer,
+            honba,
+            kyotaku,
+            players: vec![],
+            haipai: vec![],
+            phase: Phase::ENDED(RoundEnd::DRAW { tenpai: vec![] }),
+            events: vec![],
+            wall: tiles,
+            dead_wall,
+            kans: 0,
+            interrupted: false,
+            pending_riichi: None,
+            kuikae: vec![],
+        };
+
+        for _ in 0..players {
+            let hand: Vec<Tile> = round.wall.drain(..13).collect();
+            round.players.push(PlayerState {
+                hand,
+                melds: vec![],
+                discards: vec![],
+                riichi: Riichi::NONE,
+                first_turn: true,
+            });
+        }
+        // 起家から配った手を席順に並べ直す
+        round.players.rotate_right(dealer);
+        round.haipai = round.players.iter().map(|p| p.hand.clone()).collect();
+
+        round.events.push(RoundEvent::START {
+            dealer,
+            wall: round.wall.len(),
+        });
+        let first = round.dead_wall[4];
+        round.events.push(RoundEvent::DORA { indicator: first });
+        round.draw(dealer);
+        Ok(round)
+    }
+
+    /// 残りのツモ牌の枚数
+    pub fn wall_remaining(&self) -> usize {
+        self.wall.len()
+    }
+
+    /// めくれているドラ表示牌（槓ドラを含む）
+    pub fn dora_indicators(&self) -> Vec<Tile> {
+        self.dead_wall[4..5 + self.kans].to_vec()
+    }
+
+    fn ura_indicators(&self) -> Vec<Tile> {
+        self.dead_wall[9..10 + self.kans].to_vec()
+    }
+
+    pub fn seat_wind(&self, seat: usize) -> Wind {
+        let n = self.players.len();
+        match (seat + n - self.dealer) % n {
+            0 => Wind::E,
+            1 => Wind::S,
+            2 => Wind::W,
+            _ => Wind::N,
+        }
+    }
+
+    /// seat が今とれる行動
+    pub fn legal_actions(&self, seat: usize) -> Vec<Action> {
+        match &self.phase {
+            Phase::TURN { seat: s, drawn, .. } if *s == seat => self.turn_actions(seat, *drawn),
+            Phase::CLAIM {
+                from,
+                tile,
+                kan,
+                responses,
+            } if seat != *from && responses.get(seat).is_some_and(|r| r.is_none()) => {
+                self.claim_actions(seat, *from, *tile, kan.as_ref())
+            }
+            _ => vec![],
+        }
+    }
+
+    /// 行動を適用する（legal_actions にない行動はエラー）
+    pub fn apply(&mut self, seat: usize, action: Action) -> Result<(), String> {
+        if !self.legal_actions(seat).contains(&action) {
+            return Err(format!("その行動はできません: seat={}, {:?}", seat, action));
+        }
+
+        if let Phase::CLAIM { responses, .. } = &mut self.phase {
+            responses[seat] = Some(action);
+            return self.resolve_claim();
+        }
+        let Phase::TURN { drawn, .. } = self.phase else {
+            unreachable!()
+        };
+        match action {
+            Action::TSUMO => self.finish_win(seat, None, drawn.unwrap()),
+            Action::DISCARD { tile, riichi } => self.discard(seat, tile, riichi),
+            Action::KAN { meld } => self.declare_kan(seat, meld),
+            _ => unreachable!(),
+        }
+    }
+
+    // ===== 手番 =====
+
+    fn turn_actions(&self, seat: usize, drawn: Option<Tile>) -> Vec<Action> {
+        let p = &self.players[seat];
+        let mut actions = vec![];
+
+        if let Some(d) = drawn {
+            if self.can_win(seat, None, d) {
+                actions.push(Action::TSUMO);
+            }
+            if self.can_kan() {
+                // 立直後は待ちを変えない暗槓のみ
+                let riichi_ok = |m: &Meld| {
+                    m.meld_type == MeldType::ANKAN
+                        && check_riichi_ankan(
+                            &p.hand,
+                            p.melds.len(),
+                            d,
+                            meld_tiles(m)[0],
+                            self.rules.riichi_ankan,
+                        ) == AnkanVerdict::ALLOWED
+                };
+                actions.extend(
+                    self.kan_options(seat)
+                        .into_iter()
+                        .filter(|m| p.riichi == Riichi::NONE || riichi_ok(m))
+                        .map(|meld| Action::KAN { meld }),
+                );
+            }
+        }
+
+        if p.riichi != Riichi::NONE {
+            // 立直後はツモ切りのみ
+            if let Some(d) = drawn {
+                actions.push(Action::DISCARD {
+                    tile: d,
+                    riichi: false,
+                });
+            }
+            return actions;
+        }
+
+        let menzen = p.melds.iter().all(|m| m.meld_type == MeldType::ANKAN);
+        let can_riichi = menzen && drawn.is_some() && self.wall.len() >= self.players.len();
+        let mut seen: Vec<Tile> = vec![];
+        for &t in &p.hand {
+            if seen.contains(&t) || self.kuikae.contains(&TileKey::from_tile(&t)) {
+                continue;
+            }
+            seen.push(t);
+            actions.push(Action::DISCARD {
+                tile: t,
+                riichi: false,
+            });
+            if can_riichi {
+                let mut rest = p.hand.clone();
+                remove_tile(&mut rest, t);
+                if is_tenpai(&rest, p.melds.len()) {
+                    actions.push(Action::DISCARD {
+                        tile: t,
+                        riichi: true,
+                    });
+                }
+            }
+        }
+        actions
+    }
+
+    fn can_kan(&self) -> bool {
+        self.kans < 4 && !self.wall.is_empty()
+    }
+
+    /// 暗槓・加槓の候補
+    fn kan_options(&self, seat: usize) -> Vec<Meld> {
+        let p = &self.players[seat];
+        kan_melds(&p.hand, &p.melds)
+    }
+
+    fn discard(&mut self, seat: usize, tile: Tile, riichi: bool) -> Result<(), String> {
+        let p = &mut self.players[seat];
+        remove_tile(&mut p.hand, tile);
+        p.discards.push(tile);
+        if riichi {
+            p.riichi = if p.first_turn && !self.interrupted {
+                Riichi::DOUBLE
+            } else {
+                Riichi::RIICHI
+            };
+            self.pending_riichi = Some(seat);
+        }
+        p.first_turn = false;
+        self.kuikae.clear();
+
+        self.events.push(RoundEvent::DISCARD { seat, tile, riichi });
+        self.open_claim(seat, tile, None)
+    }
+
+    fn declare_kan(&mut self, seat: usize, kan: Meld) -> Result<(), String> {
+        let tiles = meld_tiles(&kan);
+        let added = *tiles.last().unwrap();
+        self.events.push(RoundEvent::KAN {
+            seat,
+            meld: kan.clone(),
+        });
+        if kan.meld_type == MeldType::ANKAN {
+            // 暗槓は国士無双の搶槓のみ
+            if self.rules.ankan_chankan_kokushi {
+                self.open_claim(seat, added, Some(kan))
+            } else {
+                self.complete_kan(seat, kan);
+                Ok(())
+            }
+        } else {
+            self.open_claim(seat, added, Some(kan))
+        }
+    }
+
+    fn complete_kan(&mut self, seat: usize, kan: Meld) {
+        let tiles = meld_tiles(&kan);
+        let p = &mut self.players[seat];
+        if kan.meld_type == MeldType::ANKAN {
+            for t in &tiles {
+                remove_tile(&mut p.hand, *t);
+            }
+            p.melds.push(kan.clone());
+        } else {
+            // 加槓：ポンを明槓にする
+            remove_tile(&mut p.hand, *tiles.last().unwrap());
+            let k = meld_key(&kan);
+            let pon = p
+                .melds
+                .iter_mut()
+                .find(|m| m.meld_type == MeldType::PON && meld_key(m) == k)
+                .unwrap();
+            *pon = kan.clone();
+        }
+        self.interrupted = true;
+        self.kan_draw(seat);
+    }
+
+    /// 槓ドラをめくり、嶺上牌をツモる（王牌を14枚に保つため山の最後を1枚減らす）
+    fn kan_draw(&mut self, seat: usize) {
+        self.kans += 1;
+        let indicator = self.dead_wall[4 + self.kans];
+        self.events.push(RoundEvent::DORA { indicator });
+        self.wall.pop();
+        let tile = self.dead_wall[self.kans - 1];
+        self.players[seat].hand.push(tile);
+        self.events.push(RoundEvent::DRAW {
+            seat,
+            tile,
+            rinshan: true,
+        });
+        self.phase = Phase::TURN {
+            seat,
+            drawn: Some(tile),
+            rinshan: true,
+        };
+    }
+
+    fn draw(&mut self, seat: usize) {
+        if self.wall.is_empty() {
+            let tenpai = self
+                .players
+                .iter()
+                .map(|p| is_tenpai(&p.hand, p.melds.len()))
+                .collect();
+            self.phase = Phase::ENDED(RoundEnd::DRAW { tenpai });
+            return;
+        }
+        let tile = self.wall.remove(0);
+        self.players[seat].hand.push(tile);
+        self.events.push(RoundEvent::DRAW {
+            seat,
+            tile,
+            rinshan: false,
+        });
+        self.phase = Phase::TURN {
+            seat,
+            drawn: Some(tile),
+            rinshan: false,
+        };
+    }
+
+    // ===== 他家の応答 =====
+
+    fn claim_actions(
+        &self,
+        seat: usize,
+        from: usize,
+        tile: Tile,
+        kan: Option<&Meld>,
+    ) -> Vec<Action> {
+        let mut actions = vec![];
+        if self.can_win(seat, Some(from), tile) {
+            actions.push(Action::RON);
+        }
+
+        // 槓への応答は搶槓のみ。河底牌と立直者は鳴けない
+        if kan.is_none() && !self.wall.is_empty() && self.players[seat].riichi == Riichi::NONE {
+            actions.extend(
+                self.call_options(seat, from, tile)
+                    .into_iter()
+                    .map(|meld| Action::CALL { meld }),
+            );
+        }
+
+        if !actions.is_empty() {
+            actions.push(Action::PASS);
+        }
+        actions
+    }
+
+    /// 捨て牌へのチー・ポン・明槓の候補（赤五の使い分けも別の候補）
+    fn call_options(&self, seat: usize, from: usize, tile: Tile) -> Vec<Meld> {
+        call_melds(&self.players[seat].hand, tile, seat, from, &self.rules)
+            .into_iter()
+            .map(|(m, _)| m)
+            .filter(|m| m.meld_type != MeldType::MINKAN || self.can_kan())
+            .collect()
+    }
+
+    fn open_claim(&mut self, from: usize, tile: Tile, kan: Option<Meld>) -> Result<(), String> {
+        self.phase = Phase::CLAIM {
+            from,
+            tile,
+            kan: kan.clone(),
+            responses: vec![None; self.players.len()],
+        };
+        // 何もできない席は見送り扱い
+        for seat in 0..self.players.len() {
+            if seat == from
+                || self
+                    .claim_actions(seat, from, tile, kan.as_ref())
+                    .is_empty()
+            {
+                if let Phase::CLAIM { responses, .. } = &mut self.phase {
+                    responses[seat] = Some(Action::PASS);
+                }
+            }
+        }
+        self.resolve_claim()
+    }
+
+    /// 全員の応答が揃ったら、ロン > ポン・明槓 > チー の順で処理する
+    fn resolve_claim(&mut self) -> Result<(), String> {
+        let Phase::CLAIM {
+            from,
+            tile,
+            kan,
+            responses,
+        } = &self.phase
+        else {
+            return Ok(());
+        };
+        if responses.iter().any(|r| r.is_none()) {
+            return Ok(());
+        }
+        let (from, tile, kan) = (*from, *tile, kan.clone());
+        let n = self.players.len();
+        let order: Vec<usize> = (1..n).map(|i| (from + i) % n).collect();
+        let responses: Vec<Action> = responses.iter().map(|r| r.clone().unwrap()).collect();
+
+        // 頭ハネ：放銃者から近い順に1人だけ
+        if let Some(&winner) = order.iter().find(|&&s| responses[s] == Action::RON) {
+            return self.finish_win(winner, Some(from), tile);
+        }
+
+        // 立直宣言牌が通った
+        if self.pending_riichi.take().is_some() {
+            self.kyotaku += 1;
+        }
+
+        if let Some(kan) = kan {
+            self.complete_kan(from, kan);
+            return Ok(());
+        }
+
+        let call = order
+            .iter()
+            .filter_map(|&s| match &responses[s] {
+                Action::CALL { meld } => Some((s, meld.clone())),
+                _ => None,
+            })
+            .min_by_key(|(_, m)| (m.meld_type == MeldType::CHI) as u8);
+
+        match call {
+            Some((seat, m)) => self.call(seat, from, m),
+            None => self.draw((from + 1) % n),
+        }
+        Ok(())
+    }
+
+    fn call(&mut self, seat: usize, from: usize, m: Meld) {
+        let mut tiles = meld_tiles(&m);
+        let called = self.players[from].discards.last().copied().unwrap();
+        let pos = tiles.iter().position(|t| *t == called).unwrap();
+        tiles.remove(pos);
+        for t in tiles {
+            remove_tile(&mut self.players[seat].hand, t);
+        }
+        self.players[seat].melds.push(m.clone());
+        self.kuikae = kuikae(&m, called, self.rules.kuikae);
+
+        self.interrupted = true;
+        self.events.push(RoundEvent::CALL {
+            seat,
+            from,
+            meld: m.clone(),
+        });
+
+        if m.meld_type == MeldType::MINKAN {
+            self.kan_draw(seat);
+        } else {
+            self.phase = Phase::TURN {
+                seat,
+                drawn: None,
+                rinshan: false,
+            };
+        }
+    }
+
+    // ===== 和了 =====
+
+    /// 和了したときの ScoreRequest（状況役のフラグとロンのフリテンは局の記録から決める）
+    pub fn score_request(
+        &self,
+        winner: usize,
+        loser: Option<usize>,
+        win_tile: Tile,
+    ) -> Result<ScoreRequest, String> {
+        let p = &self.players[winner];
+        let win_type = if loser.is_none() {
+            WinType::TSUMO
+        } else {
+            WinType::RON
+        };
+        let mut hand = p.hand.clone();
+        if win_type == WinType::TSUMO {
+            remove_tile(&mut hand, win_tile);
+        }
+        let flags = derive_flags(&self.events, winner, win_type)?;
+
+        let codes = |tiles: &[Tile]| tiles.iter().map(|t| t.to_code()).collect::<Vec<_>>();
+        let dora = self.dora_indicators();
+        let ura = self.ura_indicators();
+
+        Ok(ScoreRequest {
+            round_wind: self.round_wind,
+            seat_wind: self.seat_wind(winner),
+            kyotaku: self.kyotaku,
+            honba: self.honba,
+            win_type,
+            dealer: winner == self.dealer,
+            hand_tiles: codes(&hand),
+            win_tile: win_tile.to_code(),
+            melds: p.melds.clone(),
+            dora_indicators: codes(&dora[..1]),
+            kan_dora_indicators: codes(&dora[1..]),
+            ura_indicators: codes(&ura[..1]),
+            kan_ura_indicators: codes(&ura[1..]),
+            nukidora: 0,
+            flags,
+            events: None,
+            furiten: (win_type == WinType::RON)
+                .then(|| FuritenInfo::from_events(&self.events, winner)),
+            rules: self.rules.clone(),
+        })
+    }
+
+    /// 和了できるか（形がない・役がない・フリテンならできない）
+    /// - 形を見てから、立直か門前ツモで役が確定していれば点数計算はしない
+    fn can_win(&self, winner: usize, loser: Option<usize>, win_tile: Tile) -> bool {
+        let p = &self.players[winner];
+        let mut hand = p.hand.clone();
+        if loser.is_some() {
+            hand.push(win_tile);
+        }
+        if !is_complete(&hand, p.melds.len()) {
+            return false;
+        }
+        let Ok(request) = self.score_request(winner, loser, win_tile) else {
+            return false;
+        };
+        let menzen = p.melds.iter().all(|m| m.meld_type == MeldType::ANKAN);
+        let sure_yaku = (request.flags.riichi != Riichi::NONE
+            || (menzen && request.win_type == WinType::TSUMO))
+            && request.flags.robbed_kan != Some(RobbedKan::ANKAN)
+            && self
+                .rules
+                .shibari
+                .is_none_or(|sb| self.honba < sb.honba || sb.han <= 1);
+        if sure_yaku && matches!(ron_furiten(&request), Ok(None)) {
+            return true;
+        }
+        score(&request).total_points > 0
+    }
+
+    fn finish_win(
+        &mut self,
+        winner: usize,
+        loser: Option<usize>,
+        win_tile: Tile,
+    ) -> Result<(), String> {
+        let request = self.score_request(winner, loser, win_tile)?;
+        let result = score(&request);
+        self.phase = Phase::ENDED(RoundEnd::WIN {
+            winner,
+            loser,
+            request: Box::new(request),
+            result,
+        });
+        Ok(())
+    }
+}
+
+/// 136枚（aka 枚の5を赤五に：萬・筒・索・筒の順）
+fn build_tiles(aka: u32) -> Vec<Tile> {
+    let mut tiles = vec![];
+    for k in TileKey::all() {
+        for _ in 0..4 {
+            tiles.push(k.to_tile());
+        }
+    }
+    for i in 0..aka as usize {
+        let suit = [Suit::Man, Suit::Pin, Suit::Sou, Suit::Pin][i % 4];
+        if let Some(t) = tiles
+            .iter_mut()
+            .find(|t| t.suit == suit && t.num == 5 && !t.red)
+        {
+            t.red = true;
+        }
+    }
+    tiles
+}
+
+/// splitmix64 による Fisher-Yates
+fn shuffle(tiles: &mut [Tile], seed: u64) {
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    };
+    for i in (1..tiles.len()).rev() {
+        let j = (next() % (i as u64 + 1)) as usize;
+        tiles.swap(i, j);
+    }
+}
+
+fn remove_tile(hand: &mut Vec<Tile>, tile: Tile) {
+    if let Some(i) = hand.iter().position(|t| *t == tile) {
+        hand.remove(i);
+    }
+}
@@ -0,0 +1,53 @@
+use crate::preset::rules_from_value;
+use crate::rules::RuleSet;
+use serde_json::Value;
+use std::path::Path;
+
+/// ルールファイルの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleFormat {
+    TOML,
+    JSON,
+}
+
+impl RuleFormat {
+    /// 拡張子（.toml / .json）から形式を決める
+    pub fn from_path(path: &Path) -> Result<RuleFormat, String> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Ok(RuleFormat::TOML),
+            Some("json") => Ok(RuleFormat::JSON),
+            _ => Err(format!(
+                "ルールファイルは .toml か .json にしてください: {}",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// ルールファイルの読み込み
+/// - 書式はリクエストの rules と同じ（"preset" で既存プリセットを上書きできる）
+/// - 知らない項目はエラー
+pub fn parse_rules(text: &str, format: RuleFormat) -> Result<RuleSet, String> {
+    let value: Value = match format {
+        RuleFormat::TOML => {
+            let t: toml::Table = toml::from_str(text).map_err(|e| e.to_string())?;
+            serde_json::to_value(t).map_err(|e| e.to_string())?
+        }
+        RuleFormat::JSON => serde_json::from_str(text).map_err(|e| e.to_string())?,
+    };
+    rules_from_value(value)
+}
+
+pub fn write_rules(rules: &RuleSet, format: RuleFormat) -> Result<String, String> {
+    match format {
+        RuleFormat::TOML => toml::to_string(rules).map_err(|e| e.to_string()),
+        RuleFormat::JSON => serde_json::to_string_pretty(rules).map_err(|e| e.to_string()),
+    }
+}
+
+pub fn load_rules_file(path: &Path) -> Result<RuleSet, String> {
+    let format = RuleFormat::from_path(path)?;
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("ルールファイルを読めません: {}: {}", path.display(), e))?;
+    parse_rules(&text, format).map_err(|e| format!("{}: {}", path.display(), e))
+}
@@ -0,0 +1,321 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 終局時に残った供託の扱い
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum LeftoverKyotaku {
+    /// トップ取り
+    TOP,
+    /// 没収（誰のものにもならない）
+    VANISH,
+}
+
+/// 素点を千点単位に直すときの丸め方
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ScoreRounding {
+    /// 丸めない（100点 = 0.1）
+    EXACT,
+    /// 四捨五入
+    ROUND,
+    /// 五捨六入
+    GOSHA,
+    /// 切り捨て
+    FLOOR,
+    /// 切り上げ
+    CEIL,
+}
+
+/// 対局の長さ
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum GameLength {
+    /// 東風戦
+    TONPUU,
+    /// 半荘戦
+    HANCHAN,
+}
+
+/// 三麻のツモ和了で不在の1人分をどうするか
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum SanmaTsumo {
+    /// ツモ損（不在者の分は支払われない）
+    LOSS,
+    /// 不在者の分を2人で折半
+    SPLIT,
+}
+
+/// 錯和（チョンボ）の罰符の払い方
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ChomboPayment {
+    /// 満貫相当をその場でほかの全員へ支払う（満貫ツモの逆）
+    PAY,
+    /// 終局時に最終スコアから chombo_deduction を差し引く
+    DEDUCT,
+}
+
+/// フリテンでのロンの扱い
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum FuritenRon {
+    /// 和了を認めない（入力エラー）
+    ERROR,
+    /// 錯和（チョンボ）として扱う（罰符は呼び出し側が GameLedger::record_chombo で精算する）
+    CHOMBO,
+}
+
+/// 喰い替え（チー・ポンの直後に切れない牌）
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum KuikaeRule {
+    /// 制限なし
+    ALLOW,
+    /// 鳴いた牌と同じ牌（現物）のみ禁止
+    GENBUTSU,
+    /// 現物と、チーした順子の反対側の筋も禁止
+    SUJI,
+}
+
+/// 立直後の暗槓（送り槓と待ちが変わる暗槓はどちらも不可）
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum RiichiAnkanRule {
+    /// 待ちが変わらなければ可
+    LENIENT,
+    /// 待ちに加えて面子構成も変わらなければ可
+    STRICT,
+}
+
+/// 人和の扱い
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum RenhouRule {
+    /// なし
+    #[default]
+    OFF,
+    /// 満貫
+    MANGAN,
+    /// 役満
+    YAKUMAN,
+}
+
+/// ローカル役（すべて既定で無効）
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LocalYakuRules {
+    pub renhou: RenhouRule,
+    /// 大車輪（筒子）
+    pub daisharin: bool,
+    /// 大数隣（萬子）
+    pub daisuurin: bool,
+    /// 大竹林（索子）
+    pub daichikurin: bool,
+    /// 三連刻
+    pub sanrenkou: bool,
+    /// 一色三同順
+    pub iishoku_sandoujun: bool,
+    /// 一筒摸月（満貫）
+    pub iipin_moyue: bool,
+    /// 九筒撈魚（満貫）
+    pub chuupin_raoyui: bool,
+    /// オープン立直
+    pub open_riichi: bool,
+    /// 十三不塔（役満）
+    pub shiisanputaa: bool,
+    /// 燕返し
+    pub tsubame_gaeshi: bool,
+}
+
+/// 縛り：honba 本場以上では、ドラを除く役が han 翻以上ないと和了できない
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Shibari {
+    pub honba: u32,
+    pub han: u32,
+}
+
+/// 13翻以上の扱い
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum KazoeRule {
+    /// 数え役満
+    YAKUMAN,
+    /// 三倍満止まり
+    SANBAIMAN,
+}
+
+/// 役満の倍率と複合の扱い
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct YakumanRules {
+    /// 役満ごとの倍率（役名 → 倍率、ここにない役満は1倍）
+    pub multipliers: BTreeMap<String, u32>,
+    /// 複数の役満の複合を認める（false なら倍率が最大の1役のみ）
+    pub stacking: bool,
+    /// 合計倍率の上限（1〜6）
+    pub cap: u32,
+}
+
+impl Default for YakumanRules {
+    fn default() -> Self {
+        let multipliers = ["大四喜", "四暗刻単騎", "純正九蓮宝燈", "国士無双十三面待ち"]
+            .into_iter()
+            .map(|name| (name.to_string(), 2))
+            .collect();
+        Self {
+            multipliers,
+            stacking: true,
+            cap: 6,
+        }
+    }
+}
+
+/// 副露手で符のないツモ（喰い平和形）の符
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OpenPinfuFu {
+    /// ツモ符2符を足して 22符 → 切り上げ30符
+    ROUNDUP,
+    /// ツモ符なしの30符固定
+    FIXED,
+}
+
+/// ルールの書式バージョン（項目を追加しても古いファイルはそのまま読める）
+/// - 読み込みで拒否するのはこれより新しいバージョンだけ。古いバージョンは変換せずに読む
+/// - 既存項目の意味や形を変えるときはバージョンを上げ、rules_from_value で古い書式を変換すること
+pub const RULES_VERSION: u32 = 1;
+
+/// 対局ルール一式（省略した項目は既定値）
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RuleSet {
+    /// 書式バージョン（RULES_VERSION 以下）
+    pub version: u32,
+
+    // ===== 精算（ウマ・オカ） =====
+    /// 配給原点（例: 25000）
+    pub start_points: i32,
+    /// 返し（例: 30000）。(返し - 配給原点) × 人数 がオカとしてトップへ
+    pub return_points: i32,
+    /// 順位ウマ（千点単位、1位から順）。例: 10-30 なら [30, 10, -10, -30]。三麻では3つ
+    pub uma: Vec<i32>,
+    pub leftover_kyotaku: LeftoverKyotaku,
+    pub rounding: ScoreRounding,
+
+    // ===== 終局条件 =====
+    pub game_length: GameLength,
+    /// 持ち点が0未満になった時点で終局（トビ）
+    pub tobi: bool,
+    /// オーラスの親が和了してトップなら終局できる（アガリやめ）
+    pub agari_yame: bool,
+    /// オーラスの親が聴牌流局でトップなら終局できる（テンパイやめ）
+    pub tenpai_yame: bool,
+    /// 規定局終了時に誰も extension_target に届いていなければ次の場へ延長（西入）
+    pub extension: bool,
+    pub extension_target: i32,
+
+    // ===== チョンボ =====
+    pub chombo_payment: ChomboPayment,
+    /// DEDUCT のときに差し引く点数（例: 20000 = 20.0）
+    pub chombo_deduction: i32,
+    /// チョンボの局をやり直す（同じ局・同じ本場）。false なら親を流して次局へ
+    pub chombo_replay: bool,
+    /// フリテンでロンしたとき
+    pub furiten_ron: FuritenRon,
+
+    // ===== 点数計算 =====
+    /// 喰いタン
+    pub kuitan: bool,
+    /// 喰い替え
+    pub kuikae: KuikaeRule,
+    /// 立直後の暗槓
+    pub riichi_ankan: RiichiAnkanRule,
+    /// 一発
+    pub ippatsu: bool,
+    /// 裏ドラ（槓裏を含む）
+    pub ura_dora: bool,
+    /// 槓ドラ
+    pub kan_dora: bool,
+    /// 赤ドラの枚数（0 なら赤五も普通の5として扱う）
+    pub aka_dora: u32,
+    /// 1本場あたりの積み棒（ロンはそのまま、ツモは1人あたり 1/3）
+    pub honba_points: u32,
+    pub kazoe: KazoeRule,
+    /// 切り上げ満貫（4翻30符・3翻60符を満貫にする）
+    pub kiriage: bool,
+    pub yakuman: YakumanRules,
+    pub local_yaku: LocalYakuRules,
+    /// 例: 5本場以上で二翻縛り = Some(Shibari { honba: 5, han: 2 })
+    pub shibari: Option<Shibari>,
+    /// 暗槓への搶槓を国士無双に限り認める
+    pub ankan_chankan_kokushi: bool,
+    /// 嶺上開花にツモ符2符をつける
+    pub rinshan_tsumo_fu: bool,
+    pub open_pinfu_fu: OpenPinfuFu,
+
+    // ===== 三人麻雀 =====
+    /// 三麻（2m〜8m 抜き、北抜きドラ）
+    pub sanma: bool,
+    pub sanma_tsumo: SanmaTsumo,
+    /// 四麻でも北抜きドラを使う
+    pub yonma_nukidora: bool,
+}
+
+impl RuleSet {
+    pub fn players(&self) -> usize {
+        if self.sanma {
+            3
+        } else {
+            4
+        }
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        RuleSet {
+            version: RULES_VERSION,
+            start_points: 25000,
+            return_points: 30000,
+            uma: vec![20, 10, -10, -20],
+            leftover_kyotaku: LeftoverKyotaku::TOP,
+            rounding: ScoreRounding::EXACT,
+
+            game_length: GameLength::HANCHAN,
+            tobi: true,
+            agari_yame: true,
+            tenpai_yame: true,
+            extension: true,
+            extension_target: 30000,
+
+            chombo_payment: ChomboPayment::PAY,
+            chombo_deduction: 20000,
+            chombo_replay: true,
+            furiten_ron: FuritenRon::ERROR,
+
+            kuitan: true,
+            kuikae: KuikaeRule::SUJI,
+            riichi_ankan: RiichiAnkanRule::STRICT,
+            ippatsu: true,
+            ura_dora: true,
+            kan_dora: true,
+            aka_dora: 3,
+            honba_points: 300,
+            kazoe: KazoeRule::YAKUMAN,
+            kiriage: false,
+            yakuman: YakumanRules::default(),
+            local_yaku: LocalYakuRules::default(),
+            shibari: None,
+            ankan_chankan_kokushi: true,
+            rinshan_tsumo_fu: true,
+            open_pinfu_fu: OpenPinfuFu::ROUNDUP,
+
+            sanma: false,
+            sanma_tsumo: SanmaTsumo::LOSS,
+            yonma_nukidora: false,
+        }
+    }
+}
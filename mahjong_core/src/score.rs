@@ -1,6 +1,44 @@
-use crate::engine::score_best;
+use crate::engine::{error_result, score_best, validate_request};
+use crate::flags::with_event_flags;
+use crate::furiten::ron_furiten;
+use crate::rules::FuritenRon;
 use crate::{ScoreRequest, ScoreResult};
 
+/// events 付きのリクエストは状況役フラグを記録から決めてから計算する
+/// - フリテンのロンは rules.furiten_ron に従ってエラーかチョンボにする
+/// - 入力の確認はここで1回だけ行う（score_best は確認しない）
 pub fn score(req: &ScoreRequest) -> ScoreResult {
-    score_best(req)
+    let derived = match with_event_flags(req) {
+        Ok(derived) => derived,
+        Err(e) => return error_result(e),
+    };
+    let req = derived.as_ref().unwrap_or(req);
+    if let Err(e) = validate_request(req) {
+        return error_result(e);
+    }
+    match ron_furiten(req) {
+        Ok(None) => score_best(req),
+        Ok(Some(f)) => match req.rules.furiten_ron {
+            FuritenRon::ERROR => error_result(format!("{}のためロンできません", f.label())),
+            FuritenRon::CHOMBO => ScoreResult {
+                chombo: true,
+                ..error_result(format!("{}のロンはチョンボです", f.label()))
+            },
+        },
+        Err(e) => error_result(e),
+    }
+}
+
+/// 入力（牌コード・ルール上の制限・状況役フラグ・フリテン）の整合性チェック
+/// - フリテンのロンがエラーになるのは rules.furiten_ron が ERROR のときのみ
+pub fn validate(req: &ScoreRequest) -> Result<(), String> {
+    let derived = with_event_flags(req)?;
+    let req = derived.as_ref().unwrap_or(req);
+    validate_request(req)?;
+    match ron_furiten(req)? {
+        Some(f) if req.rules.furiten_ron == FuritenRon::ERROR => {
+            Err(format!("{}のためロンできません", f.label()))
+        }
+        _ => Ok(()),
+    }
 }
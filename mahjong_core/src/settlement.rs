@@ -0,0 +1,85 @@
+use crate::rules::{LeftoverKyotaku, RuleSet, ScoreRounding};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FinalStanding {
+    /// 起家から数えた席（0 = 起家）
+    pub seat: usize,
+    /// 順位（1始まり）
+    pub rank: u32,
+    /// 素点（供託トップ取りを含む）
+    pub raw_points: i32,
+    /// ウマ・オカ込みの最終スコア（千点単位、±）
+    pub score: f64,
+}
+
+/// 終局精算：順位・素点・ウマオカ込みスコアを計算する
+/// - points: 起家から順の持ち点
+/// - kyotaku: 卓上に残っている供託本数
+/// - 同点は起家に近い方が上位
+pub fn settle_final(
+    points: &[i32],
+    kyotaku: u32,
+    rules: &RuleSet,
+) -> Result<Vec<FinalStanding>, String> {
+    let players = points.len();
+    if players == 0 {
+        return Err("持ち点がありません".into());
+    }
+    if rules.uma.len() != players {
+        return Err(format!(
+            "ウマの数({})が人数({})と一致しません",
+            rules.uma.len(),
+            players
+        ));
+    }
+
+    let order = rank_order(points);
+
+    // オカ（100点単位）
+    let oka = (rules.return_points - rules.start_points) * players as i32 / 100;
+
+    let mut standings = vec![];
+    for (i, &seat) in order.iter().enumerate() {
+        let mut raw = points[seat];
+        if i == 0 && rules.leftover_kyotaku == LeftoverKyotaku::TOP {
+            raw += 1000 * kyotaku as i32;
+        }
+
+        // 100点単位で計算して最後に千点単位へ
+        let mut score = (round_points(raw, rules.rounding) - rules.return_points) / 100;
+        score += rules.uma[i] * 10;
+        if i == 0 {
+            score += oka;
+        }
+
+        standings.push(FinalStanding {
+            seat,
+            rank: i as u32 + 1,
+            raw_points: raw,
+            score: score as f64 / 10.0,
+        });
+    }
+
+    standings.sort_by_key(|s| s.seat);
+    Ok(standings)
+}
+
+/// 順位順の席番号（点数降順、同点は起家に近い方が上位）
+pub fn rank_order(points: &[i32]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&a, &b| points[b].cmp(&points[a]).then(a.cmp(&b)));
+    order
+}
+
+fn round_points(raw: i32, mode: ScoreRounding) -> i32 {
+    let below = raw.rem_euclid(1000);
+    let floor = raw - below;
+    match mode {
+        ScoreRounding::EXACT => raw.div_euclid(100) * 100,
+        ScoreRounding::ROUND if below >= 500 => floor + 1000,
+        ScoreRounding::GOSHA if below >= 600 => floor + 1000,
+        ScoreRounding::CEIL if below > 0 => floor + 1000,
+        _ => floor,
+    }
+}
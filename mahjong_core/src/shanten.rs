@@ -0,0 +1,119 @@
+use crate::tile::{Tile, TileKey};
+
+/// 向聴数（聴牌 0、和了形 -1）
+/// - hand: 副露を除いた手牌（3n+1枚 または 3n+2枚）
+/// - meld_count: 副露（暗槓を含む）の数
+/// - 七対子・国士無双は副露なしのときのみ
+pub fn shanten(hand: &[Tile], meld_count: usize) -> i32 {
+    let mut counts = [0u8; 34];
+    for t in hand {
+        counts[TileKey::from_tile(t).index()] += 1;
+    }
+
+    let mut best = standard_shanten(&mut counts, meld_count as i32);
+    if meld_count == 0 && hand.len() >= 13 {
+        best = best.min(chiitoitsu_shanten(&counts));
+        best = best.min(kokushi_shanten(&counts));
+    }
+    best
+}
+
+/// 4面子1雀頭
+fn standard_shanten(counts: &mut [u8; 34], melds: i32) -> i32 {
+    let mut best = search(counts, 0, melds, 0, false);
+    for i in 0..34 {
+        if counts[i] >= 2 {
+            counts[i] -= 2;
+            best = best.min(search(counts, 0, melds, 0, true));
+            counts[i] += 2;
+        }
+    }
+    best
+}
+
+/// i 番目以降から面子・塔子を抜いたときの最小向聴数
+fn search(counts: &mut [u8; 34], mut i: usize, melds: i32, taatsu: i32, pair: bool) -> i32 {
+    while i < 34 && counts[i] == 0 {
+        i += 1;
+    }
+    if i == 34 {
+        let taatsu = taatsu.min(4 - melds);
+        return 8 - 2 * melds - taatsu - pair as i32;
+    }
+
+    let suited = i < 27;
+    let pos = i % 9;
+    let mut best;
+
+    // 面子
+    if counts[i] >= 3 {
+        counts[i] -= 3;
+        best = search(counts, i, melds + 1, taatsu, pair);
+        counts[i] += 3;
+    } else {
+        best = i32::MAX;
+    }
+    if suited && pos <= 6 && counts[i + 1] > 0 && counts[i + 2] > 0 {
+        take(counts, &[i, i + 1, i + 2]);
+        best = best.min(search(counts, i, melds + 1, taatsu, pair));
+        put(counts, &[i, i + 1, i + 2]);
+    }
+
+    // 塔子（対子・両面/辺張・嵌張）
+    if melds + taatsu < 4 {
+        if counts[i] >= 2 {
+            counts[i] -= 2;
+            best = best.min(search(counts, i, melds, taatsu + 1, pair));
+            counts[i] += 2;
+        }
+        if suited && pos <= 7 && counts[i + 1] > 0 {
+            take(counts, &[i, i + 1]);
+            best = best.min(search(counts, i, melds, taatsu + 1, pair));
+            put(counts, &[i, i + 1]);
+        }
+        if suited && pos <= 6 && counts[i + 2] > 0 {
+            take(counts, &[i, i + 2]);
+            best = best.min(search(counts, i, melds, taatsu + 1, pair));
+            put(counts, &[i, i + 2]);
+        }
+    }
+
+    // 孤立牌として外す
+    let n = counts[i];
+    counts[i] = 0;
+    best = best.min(search(counts, i + 1, melds, taatsu, pair));
+    counts[i] = n;
+    best
+}
+
+fn take(counts: &mut [u8; 34], idx: &[usize]) {
+    for &i in idx {
+        counts[i] -= 1;
+    }
+}
+
+fn put(counts: &mut [u8; 34], idx: &[usize]) {
+    for &i in idx {
+        counts[i] += 1;
+    }
+}
+
+/// 七対子（同じ牌4枚は2対子と数えない）
+fn chiitoitsu_shanten(counts: &[u8; 34]) -> i32 {
+    let pairs = counts.iter().filter(|&&c| c >= 2).count() as i32;
+    let kinds = counts.iter().filter(|&&c| c >= 1).count() as i32;
+    6 - pairs + (7 - kinds).max(0)
+}
+
+/// 国士無双
+fn kokushi_shanten(counts: &[u8; 34]) -> i32 {
+    let yaochu = (0..34).filter(|&i| i >= 27 || i % 9 == 0 || i % 9 == 8);
+    let (mut kinds, mut pair) = (0, false);
+    for i in yaochu {
+        if counts[i] >= 1 {
+            kinds += 1;
+        }
+        pair |= counts[i] >= 2;
+    }
+    13 - kinds - pair as i32
+}
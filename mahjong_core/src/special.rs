@@ -6,7 +6,8 @@ use crate::tile::{Suit, Tile, TileKey};
 pub enum SpecialHand {
     Chiitoitsu,
     Kokushi,
-    Kokushi13, // 国士無双十三面待ち（ダブル役満扱い）
+    Kokushi13,    // 国士無双十三面待ち（ダブル役満扱い）
+    Shiisanputaa, // 十三不塔（ローカル役）
 }
 
 /// tiles14 は「手牌13 + 和了牌1」を想定
@@ -75,9 +76,7 @@ fn detect_kokushi(tiles14: &[Tile], win_tile: Tile) -> Option<SpecialHand> {
         }
     }
 
-    let Some(pk) = pair_key else {
-        return None;
-    };
+    let pk = pair_key?;
 
     let wk = TileKey::from_tile(&win_tile);
 
@@ -90,6 +89,29 @@ fn detect_kokushi(tiles14: &[Tile], win_tile: Tile) -> Option<SpecialHand> {
     }
 }
 
+/// 十三不塔：対子が1つだけで、他に対子・搭子（隣接/嵌張）が1つもない14枚
+/// （第一ツモでの和了かどうかは呼び出し側で判定する）
+pub fn is_shiisanputaa(tiles14: &[Tile], has_calls: bool) -> bool {
+    if has_calls || tiles14.len() != 14 {
+        return false;
+    }
+
+    let mut counts: HashMap<TileKey, u8> = HashMap::new();
+    for t in tiles14 {
+        *counts.entry(TileKey::from_tile(t)).or_insert(0) += 1;
+    }
+    if counts.values().any(|&c| c > 2) || counts.values().filter(|&&c| c == 2).count() != 1 {
+        return false;
+    }
+
+    counts.keys().all(|a| {
+        a.suit == Suit::Honor
+            || counts
+                .keys()
+                .all(|b| b == a || b.suit != a.suit || a.num.abs_diff(b.num) > 2)
+    })
+}
+
 fn is_terminal_or_honor(t: &Tile) -> bool {
     if t.suit == Suit::Honor {
         return true;
@@ -1,3 +1,5 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Suit {
     Man,
@@ -74,6 +76,31 @@ impl Tile {
         }
     }
 
+    /// from_code の逆（赤五は "0m" など）
+    pub fn to_code(&self) -> String {
+        match (self.suit, self.honor) {
+            (Suit::Honor, Some(h)) => match h {
+                Honor::East => "E",
+                Honor::South => "S",
+                Honor::West => "W",
+                Honor::North => "N",
+                Honor::White => "P",
+                Honor::Green => "F",
+                Honor::Red => "C",
+            }
+            .to_string(),
+            (suit, _) => {
+                let s = match suit {
+                    Suit::Man => 'm',
+                    Suit::Pin => 'p',
+                    _ => 's',
+                };
+                let n = if self.red { 0 } else { self.num };
+                format!("{}{}", n, s)
+            }
+        }
+    }
+
     pub fn honor(h: Honor) -> Self {
         Tile {
             suit: Suit::Honor,
@@ -93,6 +120,19 @@ impl Tile {
     }
 }
 
+impl Serialize for Tile {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&self.to_code())
+    }
+}
+
+impl<'de> Deserialize<'de> for Tile {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(d)?;
+        Tile::from_code(&code).map_err(serde::de::Error::custom)
+    }
+}
+
 /// 🔑 分解・比較専用キー（赤牌を無視）
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct TileKey {
@@ -110,7 +150,7 @@ impl TileKey {
         }
     }
 
-    pub fn to_tile(&self) -> Tile {
+    pub fn to_tile(self) -> Tile {
         Tile {
             suit: self.suit,
             num: self.num,
@@ -119,6 +159,42 @@ impl TileKey {
         }
     }
 
+    /// all() の並びでの番号（0〜33）
+    pub fn index(&self) -> usize {
+        match (self.suit, self.honor) {
+            (Suit::Honor, Some(h)) => 27 + h as usize,
+            (Suit::Man, _) => self.num as usize - 1,
+            (Suit::Pin, _) => 8 + self.num as usize,
+            _ => 17 + self.num as usize,
+        }
+    }
+
+    /// 34種すべて（萬子・筒子・索子・字牌の順）
+    pub fn all() -> Vec<TileKey> {
+        let mut keys = vec![];
+        for suit in [Suit::Man, Suit::Pin, Suit::Sou] {
+            for num in 1..=9 {
+                keys.push(TileKey {
+                    suit,
+                    num,
+                    honor: None,
+                });
+            }
+        }
+        for h in [
+            Honor::East,
+            Honor::South,
+            Honor::West,
+            Honor::North,
+            Honor::White,
+            Honor::Green,
+            Honor::Red,
+        ] {
+            keys.push(TileKey::from_tile(&Tile::honor(h)));
+        }
+        keys
+    }
+
     pub fn next(&self) -> Option<Self> {
         if self.suit == Suit::Honor || self.num >= 9 {
             None
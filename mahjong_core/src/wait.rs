@@ -0,0 +1,56 @@
+use crate::decompose::decompose_with_blocks_needed;
+use crate::special::detect_special;
+use crate::tile::{Tile, TileKey};
+
+/// 副露を除いた手牌（3n+2枚）が和了形か
+/// - meld_count: 副露（暗槓を含む）の数
+/// - 同じ牌が5枚以上ある手は和了形としない
+pub fn is_complete(hand: &[Tile], meld_count: usize) -> bool {
+    if meld_count > 4 || hand.len() + 3 * meld_count != 14 {
+        return false;
+    }
+    if TileKey::all().into_iter().any(|k| copies(hand, k) > 4) {
+        return false;
+    }
+    if meld_count == 0 {
+        if let Some(&last) = hand.last() {
+            if detect_special(hand, last, false).is_some() {
+                return true;
+            }
+        }
+    }
+    !decompose_with_blocks_needed(hand, 4 - meld_count).is_empty()
+}
+
+/// 副露を除いた手牌（3n+1枚）の待ち牌（和了になる牌の種類）
+/// - 手の内で4枚使い切っている牌は待ちにならない（純手牌空聴は聴牌ではない）
+pub fn winning_tiles(hand: &[Tile], meld_count: usize) -> Vec<TileKey> {
+    TileKey::all()
+        .into_iter()
+        .filter(|&k| copies(hand, k) < 4 && may_wait(hand, k))
+        .filter(|k| {
+            let mut tiles = hand.to_vec();
+            tiles.push(k.to_tile());
+            is_complete(&tiles, meld_count)
+        })
+        .collect()
+}
+
+/// 聴牌しているか
+pub fn is_tenpai(hand: &[Tile], meld_count: usize) -> bool {
+    !winning_tiles(hand, meld_count).is_empty()
+}
+
+/// 和了牌になりうるか（手の牌と面子・対子を作れる牌か、国士無双の么九牌）
+fn may_wait(hand: &[Tile], key: TileKey) -> bool {
+    key.honor.is_some()
+        || key.num == 1
+        || key.num == 9
+        || hand
+            .iter()
+            .any(|t| t.honor.is_none() && t.suit == key.suit && t.num.abs_diff(key.num) <= 2)
+}
+
+fn copies(hand: &[Tile], key: TileKey) -> usize {
+    hand.iter().filter(|t| TileKey::from_tile(t) == key).count()
+}
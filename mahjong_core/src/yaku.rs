@@ -1,5 +1,6 @@
 use crate::decompose::{Block, HandPattern};
 use crate::fu::WaitType;
+use crate::rules::YakumanRules;
 use crate::special::SpecialHand;
 use crate::tile::{Honor, Suit, Tile, TileKey};
 use crate::{Riichi, WinType, Wind};
@@ -9,7 +10,52 @@ use std::collections::HashMap;
 pub struct YakuResult {
     pub yakuman: u32,
     pub han: u32,
+    /// 満貫役（一筒摸月など）があるときの翻数の下限
+    pub min_han: u32,
     pub yaku: Vec<String>,
+    /// 成立した役満の名前（倍率は rules.yakuman で決める）
+    pub yakuman_yaku: Vec<String>,
+}
+
+/// 役満の倍率・複合ルールを適用して yakuman を計算し直す
+/// - 戻り値: 適用内容の説明（役満でなければ None）
+pub fn apply_yakuman_policy(yr: &mut YakuResult, rules: &YakumanRules) -> Option<String> {
+    if yr.yakuman_yaku.is_empty() {
+        return None;
+    }
+
+    let parts: Vec<(&String, u32)> = yr
+        .yakuman_yaku
+        .iter()
+        .map(|name| (name, rules.multipliers.get(name).copied().unwrap_or(1)))
+        .collect();
+
+    let sum: u32 = parts.iter().map(|(_, m)| m).sum();
+    let stacked = if rules.stacking {
+        sum
+    } else {
+        parts.iter().map(|(_, m)| *m).max().unwrap_or(1)
+    };
+    let applied = stacked.min(rules.cap).max(1);
+    yr.yakuman = applied;
+
+    let detail = parts
+        .iter()
+        .map(|(name, m)| format!("{}×{}", name, m))
+        .collect::<Vec<_>>()
+        .join(" + ");
+    let result = if applied == sum {
+        format!("{}倍", sum)
+    } else {
+        format!("{}倍 → {}倍", sum, applied)
+    };
+    Some(format!(
+        "{} = {}（複合{}・上限{}倍）",
+        detail,
+        result,
+        if rules.stacking { "あり" } else { "なし" },
+        rules.cap
+    ))
 }
 
 pub fn eval_special_yaku(sp: SpecialHand) -> YakuResult {
@@ -17,21 +63,35 @@ pub fn eval_special_yaku(sp: SpecialHand) -> YakuResult {
         SpecialHand::Chiitoitsu => YakuResult {
             yakuman: 0,
             han: 2,
+            min_han: 0,
             yaku: vec!["七対子".into()],
+            yakuman_yaku: vec![],
         },
         SpecialHand::Kokushi => YakuResult {
             yakuman: 1,
             han: 0,
+            min_han: 0,
             yaku: vec!["国士無双".into()],
+            yakuman_yaku: vec!["国士無双".into()],
         },
         SpecialHand::Kokushi13 => YakuResult {
             yakuman: 2,
             han: 0,
+            min_han: 0,
             yaku: vec!["国士無双十三面待ち".into()],
+            yakuman_yaku: vec!["国士無双十三面待ち".into()],
+        },
+        SpecialHand::Shiisanputaa => YakuResult {
+            yakuman: 1,
+            han: 0,
+            min_han: 0,
+            yaku: vec!["十三不塔".into()],
+            yakuman_yaku: vec!["十三不塔".into()],
         },
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn eval_yaku_standard(
     pattern: &HandPattern,
     tiles14: &[Tile],
@@ -45,16 +105,15 @@ pub fn eval_yaku_standard(
     chankan: bool,
     haitei: bool,
     houtei: bool,
+    allow_kuitan: bool,
 ) -> YakuResult {
     let mut yakuman = 0;
     let mut han = 0;
     let mut yaku = vec![];
+    let mut yakuman_yaku = vec![];
 
     let menzen = pattern.menzen;
 
-    // ===== ルール差分（今は固定・後で ScoreRequest に寄せやすい） =====
-    let allow_kuitan = true;
-
     // ===== 状況役（基本） =====
     if riichi != Riichi::NONE {
         if riichi == Riichi::DOUBLE {
@@ -182,13 +241,21 @@ pub fn eval_yaku_standard(
     {
         yakuman += ym;
         for n in names {
-            yaku.push(n);
+            yaku.push(n.clone());
+            yakuman_yaku.push(n);
         }
         // 通常役を無視
         han = 0;
+        yaku = yakuman_yaku.clone();
     }
 
-    YakuResult { yakuman, han, yaku }
+    YakuResult {
+        yakuman,
+        han,
+        min_han: 0,
+        yaku,
+        yakuman_yaku,
+    }
 }
 
 // =====================
@@ -326,9 +393,7 @@ fn honitsu_chinitsu(tiles14: &[Tile], menzen: bool) -> Option<(String, u32)> {
         };
     }
 
-    let Some(_) = suit_seen else {
-        return None;
-    };
+    suit_seen?;
 
     if has_honor {
         // 混一色（門前3 / 鳴き2）
@@ -483,9 +548,7 @@ fn chanta_junchan(pattern: &HandPattern, menzen: bool) -> Option<(String, u32)>
     }
 
     // 雀頭
-    let Some(pk) = pattern.pair_key else {
-        return None;
-    };
+    let pk = pattern.pair_key?;
     if !is_terminal_or_honor_key(pk) {
         all_have_terminal_or_honor = false;
     }
@@ -541,10 +604,10 @@ fn is_shousangen(pattern: &HandPattern) -> bool {
             Block::Kantsu(t, _, _, _) => TileKey::from_tile(t),
             _ => continue,
         };
-        if k.suit == Suit::Honor {
-            if matches!(k.honor.unwrap(), Honor::White | Honor::Green | Honor::Red) {
-                dragon_triplets += 1;
-            }
+        if k.suit == Suit::Honor
+            && matches!(k.honor.unwrap(), Honor::White | Honor::Green | Honor::Red)
+        {
+            dragon_triplets += 1;
         }
     }
 
@@ -586,7 +649,7 @@ fn is_honroutou(pattern: &HandPattern) -> bool {
     // 雀頭
     pattern
         .pair_key
-        .map(|pk| is_terminal_or_honor_key(pk))
+        .map(is_terminal_or_honor_key)
         .unwrap_or(false)
 }
 
@@ -671,10 +734,10 @@ fn is_daisangen(pattern: &HandPattern) -> bool {
             Block::Kantsu(t, _, _, _) => TileKey::from_tile(t),
             _ => continue,
         };
-        if k.suit == Suit::Honor {
-            if matches!(k.honor.unwrap(), Honor::White | Honor::Green | Honor::Red) {
-                dragons += 1;
-            }
+        if k.suit == Suit::Honor
+            && matches!(k.honor.unwrap(), Honor::White | Honor::Green | Honor::Red)
+        {
+            dragons += 1;
         }
     }
     dragons == 3
@@ -690,13 +753,13 @@ fn suuushi(pattern: &HandPattern) -> Option<(u32, String)> {
             Block::Kantsu(t, _, _, _) => TileKey::from_tile(t),
             _ => continue,
         };
-        if k.suit == Suit::Honor {
-            if matches!(
+        if k.suit == Suit::Honor
+            && matches!(
                 k.honor.unwrap(),
                 Honor::East | Honor::South | Honor::West | Honor::North
-            ) {
-                wind_triplets += 1;
-            }
+            )
+        {
+            wind_triplets += 1;
         }
     }
 
@@ -719,20 +782,14 @@ fn suuushi(pattern: &HandPattern) -> Option<(u32, String)> {
 fn is_tsuuiisou(pattern: &HandPattern) -> bool {
     for b in &pattern.blocks {
         match b {
-            Block::Shuntsu(a, _, _) => {
-                if a.suit != Suit::Honor {
-                    return false;
-                }
+            Block::Shuntsu(a, _, _) if a.suit != Suit::Honor => {
+                return false;
             }
-            Block::Koutsu(t, _, _) => {
-                if t.suit != Suit::Honor {
-                    return false;
-                }
+            Block::Koutsu(t, _, _) if t.suit != Suit::Honor => {
+                return false;
             }
-            Block::Kantsu(t, _, _, _) => {
-                if t.suit != Suit::Honor {
-                    return false;
-                }
+            Block::Kantsu(t, _, _, _) if t.suit != Suit::Honor => {
+                return false;
             }
             _ => {}
         }
@@ -824,12 +881,9 @@ fn tiles_from_pattern(pattern: &HandPattern) -> Vec<Tile> {
         }
     }
     // pair（雀頭）
-    match &pattern.pair {
-        Block::Toitsu(a, b) => {
-            v.push(*a);
-            v.push(*b);
-        }
-        _ => {}
+    if let Block::Toitsu(a, b) = &pattern.pair {
+        v.push(*a);
+        v.push(*b);
     }
     v
 }
@@ -889,10 +943,8 @@ fn chuuren_poutou(pattern: &HandPattern, win_tile: Tile) -> Option<(u32, String)
     if c[1] < 3 || c[9] < 3 {
         return None;
     }
-    for n in 2..=8 {
-        if c[n] < 1 {
-            return None;
-        }
+    if c[2..=8].iter().any(|&n| n < 1) {
+        return None;
     }
     // 合計14
     let sum: u8 = (1..=9).map(|n| c[n]).sum();
@@ -909,10 +961,8 @@ fn chuuren_poutou(pattern: &HandPattern, win_tile: Tile) -> Option<(u32, String)
     if c2[1] != 3 || c2[9] != 3 {
         ok_base = false;
     }
-    for n in 2..=8 {
-        if c2[n] != 1 {
-            ok_base = false;
-        }
+    if c2[2..=8].iter().any(|&n| n != 1) {
+        ok_base = false;
     }
     let sum13: u8 = (1..=9).map(|n| c2[n]).sum();
     if sum13 != 13 {
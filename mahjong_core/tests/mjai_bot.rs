@@ -0,0 +1,146 @@
+//! MjaiBot にサーバのイベント列を順に渡し、応答を確かめる
+
+use mahjong_core::{MjaiBot, MjaiEvent, RulePreset};
+
+/// (サーバからのイベント, ボットの応答)
+const SCRIPT: &[(&str, &str)] = &[
+    (
+        r#"{"type":"hello","protocol":"mjsonp","protocol_version":3}"#,
+        r#"{"type":"join","name":"bot","room":"default"}"#,
+    ),
+    (
+        r#"{"type":"start_game","id":0,"names":["bot","b","c","d"]}"#,
+        r#"{"type":"none"}"#,
+    ),
+    // 東1局：ツモって打牌、聴牌して立直、ロン
+    (
+        r#"{"type":"start_kyoku","bakaze":"E","kyoku":1,"honba":0,"kyotaku":0,"oya":0,"dora_marker":"9s","tehais":[["1m","2m","3m","4p","5p","6p","7s","8s","9s","2p","5m","5m","N"],["?","?","?","?","?","?","?","?","?","?","?","?","?"],["?","?","?","?","?","?","?","?","?","?","?","?","?"],["?","?","?","?","?","?","?","?","?","?","?","?","?"]]}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"tsumo","actor":0,"pai":"W"}"#,
+        r#"{"type":"dahai","actor":0,"pai":"N","tsumogiri":false}"#,
+    ),
+    (
+        r#"{"type":"dahai","actor":0,"pai":"N","tsumogiri":false}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"tsumo","actor":1,"pai":"?"}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"dahai","actor":1,"pai":"1s","tsumogiri":true}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"tsumo","actor":2,"pai":"?"}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"dahai","actor":2,"pai":"9m","tsumogiri":true}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"tsumo","actor":3,"pai":"?"}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"dahai","actor":3,"pai":"E","tsumogiri":false}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"tsumo","actor":0,"pai":"3p"}"#,
+        r#"{"type":"reach","actor":0}"#,
+    ),
+    (
+        r#"{"type":"reach","actor":0}"#,
+        r#"{"type":"dahai","actor":0,"pai":"W","tsumogiri":false}"#,
+    ),
+    (
+        r#"{"type":"dahai","actor":0,"pai":"W","tsumogiri":false}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"reach_accepted","actor":0,"deltas":[-1000,0,0,0],"scores":[24000,25000,25000,25000]}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"tsumo","actor":1,"pai":"?"}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"dahai","actor":1,"pai":"4p","tsumogiri":true}"#,
+        r#"{"type":"hora","actor":0,"target":1,"pai":"4p"}"#,
+    ),
+    (
+        r#"{"type":"hora","actor":0,"target":1,"pai":"4p","uradora_markers":["2s"],"hora_tehais":["1m","2m","3m","4p","5p","6p","7s","8s","9s","2p","3p","5m","5m"],"yakus":[["reach",1],["ippatsu",1]],"fu":40,"fan":2,"hora_points":3900,"deltas":[4900,-3900,0,0],"scores":[28900,21100,25000,25000]}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (r#"{"type":"end_kyoku"}"#, r#"{"type":"none"}"#),
+    // 東2局：役牌をポンして打牌、ロン
+    (
+        r#"{"type":"start_kyoku","bakaze":"E","kyoku":2,"honba":0,"kyotaku":0,"oya":1,"dora_marker":"1m","tehais":[["P","P","2m","3m","4m","5p","6p","7p","3s","3s","6s","7s","C"],["?","?","?","?","?","?","?","?","?","?","?","?","?"],["?","?","?","?","?","?","?","?","?","?","?","?","?"],["?","?","?","?","?","?","?","?","?","?","?","?","?"]]}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"tsumo","actor":1,"pai":"?"}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"dahai","actor":1,"pai":"9m","tsumogiri":true}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"tsumo","actor":2,"pai":"?"}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"dahai","actor":2,"pai":"1m","tsumogiri":true}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"tsumo","actor":3,"pai":"?"}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"dahai","actor":3,"pai":"P","tsumogiri":false}"#,
+        r#"{"type":"pon","actor":0,"target":3,"pai":"P","consumed":["P","P"]}"#,
+    ),
+    (
+        r#"{"type":"pon","actor":0,"target":3,"pai":"P","consumed":["P","P"]}"#,
+        r#"{"type":"dahai","actor":0,"pai":"C","tsumogiri":false}"#,
+    ),
+    (
+        r#"{"type":"dahai","actor":0,"pai":"C","tsumogiri":false}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"tsumo","actor":1,"pai":"?"}"#,
+        r#"{"type":"none"}"#,
+    ),
+    (
+        r#"{"type":"dahai","actor":1,"pai":"8s","tsumogiri":true}"#,
+        r#"{"type":"hora","actor":0,"target":1,"pai":"8s"}"#,
+    ),
+    (r#"{"type":"end_kyoku"}"#, r#"{"type":"none"}"#),
+    (r#"{"type":"end_game"}"#, r#"{"type":"none"}"#),
+];
+
+fn event(json: &str) -> MjaiEvent {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn bot_follows_script() {
+    let mut bot = MjaiBot::new("bot", RulePreset::TENHOU.rules());
+    for (i, (input, expected)) in SCRIPT.iter().enumerate() {
+        let response = bot.react(&event(input)).unwrap();
+        assert_eq!(response, event(expected), "{}番目: {}", i, input);
+    }
+}
+
+#[test]
+fn unknown_event_type_is_none() {
+    assert_eq!(event(r#"{"type":"kan_dora","actor":0}"#), MjaiEvent::None);
+}
@@ -0,0 +1,103 @@
+//! mjai の記録を読み書きしても変わらないこと、和了が再計算と一致することを確認する
+
+use mahjong_core::{
+    parse_mjai, round_to_mjai, shanten, verify_mjai, write_mjai, Action, MeldType, MjaiEvent,
+    Phase, Round, RoundEnd, RulePreset, Wind,
+};
+use std::path::Path;
+
+fn game() -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/mjai/tenhou_game.mjson");
+    std::fs::read_to_string(path).unwrap()
+}
+
+#[test]
+fn mjai_log_round_trips() {
+    let text = game();
+    let events = parse_mjai(&text).unwrap();
+    assert_eq!(events.len(), text.lines().count());
+
+    let written = write_mjai(&events);
+    assert_eq!(parse_mjai(&written).unwrap(), events);
+    // 空の uradora_markers 以外は元の行と同じ JSON になる
+    for (orig, ours) in text.lines().zip(written.lines()) {
+        let mut orig: serde_json::Value = serde_json::from_str(orig).unwrap();
+        let ours: serde_json::Value = serde_json::from_str(ours).unwrap();
+        if orig["uradora_markers"] == serde_json::json!([]) {
+            orig.as_object_mut().unwrap().remove("uradora_markers");
+        }
+        assert_eq!(orig, ours);
+    }
+
+    let yakus: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            MjaiEvent::Hora { yakus, .. } => Some(yakus.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(yakus[0], vec![("sangenpai".to_string(), 2)]);
+}
+
+#[test]
+fn mjai_log_matches_score() {
+    let report = verify_mjai(&game(), &RulePreset::TENHOU.rules()).unwrap();
+    assert_eq!(report.rounds, 2);
+    assert_eq!(report.wins, 2);
+    assert!(
+        report.discrepancies.is_empty(),
+        "{}",
+        serde_json::to_string(&report.discrepancies).unwrap()
+    );
+    assert_eq!(report.matched, report.wins);
+}
+
+/// 和了・立直・ポンを優先し、向聴数が最も小さくなる牌を切る
+fn pick(round: &Round, seat: usize, actions: Vec<Action>) -> Action {
+    let p = &round.players[seat];
+    let best = |a: &Action| match a {
+        Action::TSUMO | Action::RON => 0,
+        Action::DISCARD { riichi: true, .. } => 1,
+        Action::CALL { meld } if meld.meld_type == MeldType::PON => 2,
+        Action::DISCARD { tile, .. } => {
+            let mut hand = p.hand.clone();
+            let i = hand.iter().position(|t| t == tile).unwrap();
+            hand.remove(i);
+            10 + shanten(&hand, p.melds.len())
+        }
+        Action::PASS => 100,
+        _ => 200,
+    };
+    actions.into_iter().min_by_key(best).unwrap()
+}
+
+#[test]
+fn played_rounds_export_to_mjai() {
+    let rules = RulePreset::TENHOU.rules();
+    let mut wins = 0;
+    for seed in 0..8 {
+        let mut round = Round::new(rules.clone(), Wind::E, seed as usize % 4, 0, 0, seed).unwrap();
+        while !matches!(round.phase, Phase::ENDED(_)) {
+            let (seat, actions) = (0..4)
+                .map(|s| (s, round.legal_actions(s)))
+                .find(|(_, a)| !a.is_empty())
+                .unwrap();
+            let action = pick(&round, seat, actions);
+            round.apply(seat, action).unwrap();
+        }
+        if matches!(round.phase, Phase::ENDED(RoundEnd::WIN { .. })) {
+            wins += 1;
+        }
+
+        let text = write_mjai(&round_to_mjai(&round));
+        let report = verify_mjai(&text, &rules).unwrap();
+        assert!(
+            report.discrepancies.is_empty(),
+            "seed {}: {}",
+            seed,
+            serde_json::to_string(&report.discrepancies).unwrap()
+        );
+        assert_eq!(report.matched, report.wins, "seed {}", seed);
+    }
+    assert!(wins > 0);
+}
@@ -0,0 +1,27 @@
+//! testdata/mjlog の牌譜を全件再計算し、天鳳の結果と一致することを確認する
+
+use mahjong_core::verify_mjlog_file;
+use std::path::Path;
+
+#[test]
+fn mjlog_corpus_matches_tenhou() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/mjlog");
+    let mut files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .collect();
+    files.sort();
+    assert!(!files.is_empty(), "{} に牌譜がありません", dir.display());
+
+    for f in &files {
+        let report = verify_mjlog_file(f).unwrap();
+        assert!(
+            report.discrepancies.is_empty(),
+            "{}: {}",
+            f.display(),
+            serde_json::to_string(&report.discrepancies).unwrap()
+        );
+        assert_eq!(report.matched, report.wins, "{}", f.display());
+        assert!(report.wins > 0, "{}: 和了がありません", f.display());
+    }
+}
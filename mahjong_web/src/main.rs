@@ -1,17 +1,24 @@
 use axum::{
-    extract::State,
+    extract::{Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
-use mahjong_core::{score as calc_score, ScoreRequest, ScoreResult};
-use std::{net::SocketAddr, sync::Arc};
+use mahjong_core::{
+    load_rules_file, rules_over, score as calc_score, score_table, RulePreset, RuleSet,
+    ScoreRequest, ScoreResult, ScoreTableRow,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::{net::SocketAddr, path::Path, sync::Arc};
 use tower_http::services::ServeDir;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[derive(Clone)]
-struct AppState {}
+struct AppState {
+    rules: RuleSet,
+}
 
 #[tokio::main]
 async fn main() {
@@ -19,10 +26,13 @@ async fn main() {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    let state = Arc::new(AppState {});
+    let state = Arc::new(AppState {
+        rules: load_default_rules(),
+    });
     let app = Router::new()
         .route("/", get(index))
         .route("/api/score", post(score))
+        .route("/api/table", get(table))
         .nest_service("/static", ServeDir::new("mahjong_web/static"))
         .with_state(state);
 
@@ -34,6 +44,29 @@ async fn main() {
         .unwrap();
 }
 
+/// 起動時のルール：MAHJONG_RULES（なければ mahjong_web/rules.toml）。ファイルがなければ既定値
+/// 読み込めないルールファイルはエラーを表示して終了する
+fn load_default_rules() -> RuleSet {
+    let (path, required) = match std::env::var("MAHJONG_RULES") {
+        Ok(p) => (p, true),
+        Err(_) => ("mahjong_web/rules.toml".to_string(), false),
+    };
+    let path = Path::new(&path);
+    if !required && !path.exists() {
+        return RuleSet::default();
+    }
+    match load_rules_file(path) {
+        Ok(rules) => {
+            println!("rules: {}", path.display());
+            rules
+        }
+        Err(e) => {
+            eprintln!("起動できません: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
 async fn index() -> Response {
     (
         StatusCode::FOUND,
@@ -43,13 +76,40 @@ async fn index() -> Response {
 }
 
 async fn score(
-    State(_state): State<Arc<AppState>>,
-    Json(req): Json<ScoreRequest>,
+    State(state): State<Arc<AppState>>,
+    Json(mut body): Json<Value>,
 ) -> Result<Json<ScoreResult>, ApiError> {
+    // rules の省略した項目（rules 自体の省略を含む）はサーバーのルールで補う
+    let rules = body
+        .as_object_mut()
+        .and_then(|obj| obj.remove("rules"))
+        .unwrap_or(Value::Null);
+    let rules = rules_over(&state.rules, rules).map_err(ApiError)?;
+    let mut req: ScoreRequest =
+        serde_json::from_value(body).map_err(|e| ApiError(e.to_string()))?;
+    req.rules = rules;
+    // 入力の確認は score が行う（エラーは yaku に入る）
     let result = calc_score(&req);
     Ok(Json(result))
 }
 
+#[derive(Deserialize)]
+struct TableQuery {
+    /// プリセット名（例: ?rules=ema）。省略時はサーバーのルール
+    rules: Option<String>,
+}
+
+async fn table(
+    State(state): State<Arc<AppState>>,
+    Query(q): Query<TableQuery>,
+) -> Result<Json<Vec<ScoreTableRow>>, ApiError> {
+    let rules = match q.rules {
+        Some(name) => RulePreset::from_name(&name).map_err(ApiError)?.rules(),
+        None => state.rules.clone(),
+    };
+    Ok(Json(score_table(&rules)))
+}
+
 struct ApiError(String);
 
 impl IntoResponse for ApiError {